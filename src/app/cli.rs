@@ -1,97 +1,719 @@
-use crate::app::help::show_help;
+use crate::app::commands::{
+    run_apply, run_auto_id, run_batch, run_check_id, run_decrypt, run_encrypt, run_ensure,
+    run_export, run_import, run_inspect, run_password, run_rekey, run_register, run_script,
+    run_serve, run_server, run_unseal, run_uuid,
+};
+use crate::app::help::localized_command;
 use crate::app::interactive::show_interactive_menu;
-use crate::app::uuid::{get_uuid_with_confirmation, UuidResult};
-use crate::core::handler::{
-    display_decrypt_success, display_encrypt_success, display_error, perform_decrypt,
-    perform_encrypt,
+use crate::app::tui::run_tui;
+use crate::core::api::{DEFAULT_BIND, TOKEN_ENV};
+use crate::core::clipboard::{
+    create_backend, set_backend, set_clear_after, wait_for_pending_clear, ClipboardMode,
+};
+use crate::core::deploy::ScriptFormat;
+use crate::core::handler::display_error;
+use crate::core::locale::{
+    available_locales, detect_locale, env_locale, normalize_locale, DEFAULT_LOCALE, LIST_LOCALES,
 };
-use clap::Parser;
+use crate::core::options::ServerSettings;
+use crate::core::sealed::PASSPHRASE_ENV;
+use crate::core::secret::{set_reveal_uuid, SecretString};
+use crate::core::uuid_key::UuidForm;
+use clap::{Args, FromArgMatches, Parser, Subcommand};
+use clap_complete::Shell;
 use rust_i18n::t;
+use std::io;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
 
 #[derive(Parser)]
-#[clap(name = "RustDesk ID Tool")]
+#[clap(name = "RustDesk ID Tool", version)]
 #[clap(about = "A tool for encrypting and decrypting RustDesk IDs", long_about = None)]
-#[clap(disable_help_flag = true)]
+#[clap(args_conflicts_with_subcommands = true)]
+#[clap(group(clap::ArgGroup::new("legacy").args(&["id", "eid"])))]
 pub struct Cli {
-    /// Custom ID to encrypt
-    #[clap(short, long)]
+    #[clap(subcommand)]
+    command: Option<Commands>,
+
+    /// Custom ID to encrypt (deprecated, use `encrypt`)
+    #[clap(short, long, conflicts_with = "eid")]
     id: Option<String>,
 
-    /// Encrypted ID to decrypt
+    /// Encrypted ID to decrypt (deprecated, use `decrypt`)
     #[clap(short, long)]
     eid: Option<String>,
 
-    /// UUID for encryption/decryption
-    #[clap(short, long)]
-    uuid: Option<String>,
+    /// UUID for encryption/decryption (deprecated, use the subcommand's --uuid)
+    #[clap(short, long, value_parser, requires = "legacy")]
+    uuid: Option<SecretString>,
+
+    /// Set the language (detected from the environment if omitted, `list` to show available languages)
+    #[clap(short, long, global = true)]
+    lang: Option<String>,
+
+    /// Clipboard backend for copying results
+    #[clap(long, global = true, value_enum, value_name = "MODE", default_value = "auto")]
+    clipboard: ClipboardMode,
+
+    /// Do not copy results to the clipboard
+    #[clap(long, global = true, conflicts_with = "clipboard")]
+    no_clipboard: bool,
 
-    /// Set the language
-    #[clap(short, long, default_value = "en")]
-    lang: String,
+    /// File that receives copied results in file mode
+    #[clap(long, global = true, value_name = "PATH")]
+    clipboard_file: Option<PathBuf>,
+
+    /// Restore or clear the clipboard after the given number of seconds
+    #[clap(long, global = true, value_name = "SECS")]
+    clipboard_clear_after: Option<u64>,
+
+    /// Show the full machine UUID instead of a masked form
+    #[clap(long, global = true)]
+    show_uuid: bool,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Encrypt a custom ID into an enc_id value
+    Encrypt(EncryptArgs),
+    /// Decrypt an enc_id value back into the custom ID
+    Decrypt(DecryptArgs),
+    /// Write a new ID and/or permanent password into RustDesk.toml
+    Apply(ApplyArgs),
+    /// Make sure RustDesk.toml holds the given ID and report the result as JSON
+    Ensure(EnsureArgs),
+    /// Re-encrypt RustDesk.toml with a new machine UUID after the old one changed
+    Rekey(RekeyArgs),
+    /// Save this machine's ID, password, key pair and server options into a passphrase-protected bundle
+    Export(ExportArgs),
+    /// Restore an exported bundle on this machine, encrypted with its UUID
+    Import(ImportArgs),
+    /// Report the ID, password and key found in RustDesk.toml
+    Inspect(InspectArgs),
+    /// Check whether a custom ID is already taken on the ID server
+    CheckId(CheckIdArgs),
+    /// Register the ID for this machine's key on the ID server before RustDesk starts
+    Register(RegisterArgs),
+    /// Show the numeric ID RustDesk derives from a MAC address when it has no usable ID
+    AutoId(AutoIdArgs),
+    /// Encrypt or decrypt the permanent password
+    #[clap(subcommand)]
+    Password(PasswordCommand),
+    /// Show or change the server options in RustDesk2.toml
+    #[clap(subcommand)]
+    Server(ServerCommand),
+    /// Encrypt or decrypt many values listed in a file
+    Batch(BatchArgs),
+    /// Decrypt a file protected by a passphrase, such as a sealed batch result or an exported bundle
+    Unseal(UnsealArgs),
+    /// Generate a script that applies the ID on another machine
+    Script(ScriptArgs),
+    /// Serve encrypt, decrypt, validate, generate and ID lookups over a local HTTP API
+    Serve(ServeArgs),
+    /// Show the detected machine UUID, or the key RustDesk derives from the given UUIDs
+    Uuid(UuidArgs),
+    /// Open the full-screen terminal UI
+    Tui(TuiArgs),
+    /// Generate a shell completion script
+    Completions {
+        /// Target shell
+        #[clap(value_enum)]
+        shell: Shell,
+    },
+    /// Generate a man page
+    Man,
+}
+
+/// UUID 参数，省略时自动检测
+#[derive(Args)]
+pub struct UuidArg {
+    /// UUID for encryption/decryption (auto-detected if omitted)
+    #[clap(short, long, value_parser)]
+    pub uuid: Option<SecretString>,
+
+    /// Convert --uuid to the form the target machine reports before using it
+    #[clap(long, value_enum, value_name = "FORM", requires = "uuid")]
+    pub uuid_form: Option<UuidForm>,
+}
+
+#[derive(Args)]
+pub struct EncryptArgs {
+    /// Custom ID to encrypt
+    pub id: String,
 
-    /// Show detailed help information
-    #[clap(short, long, action = clap::ArgAction::SetTrue)]
-    help: bool,
+    #[clap(flatten)]
+    pub uuid: UuidArg,
 }
 
-pub fn run() {
-    let cli = Cli::parse();
-    rust_i18n::set_locale(&cli.lang);
+#[derive(Args)]
+pub struct DecryptArgs {
+    /// Encrypted ID to decrypt
+    pub enc_id: String,
+
+    #[clap(flatten)]
+    pub uuid: UuidArg,
+}
+
+#[derive(Args)]
+#[clap(group(clap::ArgGroup::new("values").required(true).multiple(true).args(&["id", "password"])))]
+pub struct ApplyArgs {
+    /// Custom ID to write as enc_id
+    #[clap(long)]
+    pub id: Option<String>,
+
+    /// Permanent password to write as password
+    #[clap(long, value_parser)]
+    pub password: Option<SecretString>,
+
+    #[clap(flatten)]
+    pub uuid: UuidArg,
+
+    /// RustDesk.toml to modify (auto-detected if omitted, repeatable)
+    #[clap(long = "config", value_name = "PATH")]
+    pub configs: Vec<PathBuf>,
+
+    /// Show the changes without writing them
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// Do not keep a backup of the original file
+    #[clap(long)]
+    pub no_backup: bool,
+}
+
+#[derive(Args)]
+pub struct EnsureArgs {
+    /// Custom ID that RustDesk.toml should hold
+    #[clap(long)]
+    pub id: String,
+
+    #[clap(flatten)]
+    pub uuid: UuidArg,
+
+    /// RustDesk.toml to check (auto-detected if omitted, repeatable)
+    #[clap(long = "config", value_name = "PATH")]
+    pub configs: Vec<PathBuf>,
+
+    /// Report what would change without writing
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// Do not keep a backup of the original file
+    #[clap(long)]
+    pub no_backup: bool,
+}
+
+#[derive(Args)]
+pub struct RekeyArgs {
+    /// UUID the values in RustDesk.toml are currently encrypted with
+    #[clap(long, value_name = "UUID", value_parser)]
+    pub from_uuid: SecretString,
+
+    /// UUID to encrypt the values with (auto-detected if omitted)
+    #[clap(long, value_name = "UUID", value_parser)]
+    pub to_uuid: Option<SecretString>,
+
+    /// RustDesk.toml to re-encrypt (auto-detected if omitted, repeatable)
+    #[clap(long = "config", value_name = "PATH")]
+    pub configs: Vec<PathBuf>,
 
-    // Check if help flag is set
-    if cli.help {
-        show_help(&cli.lang);
-        return;
+    /// Show the changes without writing them
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// Do not keep a backup of the original file
+    #[clap(long)]
+    pub no_backup: bool,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Bundle file to create
+    #[clap(value_name = "BUNDLE")]
+    pub bundle: PathBuf,
+
+    #[clap(flatten)]
+    pub uuid: UuidArg,
+
+    /// RustDesk.toml to export the ID, password and key pair from (auto-detected if omitted)
+    #[clap(long = "config", value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// RustDesk2.toml to export the server options from (auto-detected if omitted)
+    #[clap(long, value_name = "PATH")]
+    pub server_config: Option<PathBuf>,
+
+    /// Passphrase protecting the bundle (prompted if omitted)
+    #[clap(long, env = PASSPHRASE_ENV, hide_env_values = true, value_parser)]
+    pub passphrase: Option<SecretString>,
+}
+
+#[derive(Args)]
+pub struct ImportArgs {
+    /// Bundle file created by export
+    #[clap(value_name = "BUNDLE")]
+    pub bundle: PathBuf,
+
+    #[clap(flatten)]
+    pub uuid: UuidArg,
+
+    /// RustDesk.toml to write the ID, password and key pair into (auto-detected if omitted, repeatable)
+    #[clap(long = "config", value_name = "PATH")]
+    pub configs: Vec<PathBuf>,
+
+    /// RustDesk2.toml to write the server options into (auto-detected if omitted, repeatable)
+    #[clap(long = "server-config", value_name = "PATH")]
+    pub server_configs: Vec<PathBuf>,
+
+    /// Passphrase protecting the bundle (prompted if omitted)
+    #[clap(long, env = PASSPHRASE_ENV, hide_env_values = true, value_parser)]
+    pub passphrase: Option<SecretString>,
+
+    /// Show the changes without writing them
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// Do not keep a backup of the original file
+    #[clap(long)]
+    pub no_backup: bool,
+}
+
+#[derive(Args)]
+pub struct InspectArgs {
+    #[clap(flatten)]
+    pub uuid: UuidArg,
+
+    /// RustDesk.toml to inspect (auto-detected if omitted, repeatable)
+    #[clap(long = "config", value_name = "PATH")]
+    pub configs: Vec<PathBuf>,
+
+    /// Print the report as JSON
+    #[clap(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct TuiArgs {
+    #[clap(flatten)]
+    pub uuid: UuidArg,
+
+    /// RustDesk.toml files to list (auto-detected if omitted, repeatable)
+    #[clap(long = "config", value_name = "PATH")]
+    pub configs: Vec<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub enum PasswordCommand {
+    /// Encrypt a permanent password (prompted if omitted)
+    Encrypt {
+        /// Password to encrypt
+        #[clap(value_parser)]
+        password: Option<SecretString>,
+
+        #[clap(flatten)]
+        uuid: UuidArg,
+    },
+    /// Decrypt an encrypted permanent password
+    Decrypt {
+        /// Encrypted password, including the 00 prefix
+        enc_password: String,
+
+        #[clap(flatten)]
+        uuid: UuidArg,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ServerCommand {
+    /// Show the server options stored in RustDesk2.toml
+    Show {
+        /// RustDesk2.toml to read (auto-detected if omitted, repeatable)
+        #[clap(long = "config", value_name = "PATH")]
+        configs: Vec<PathBuf>,
+    },
+    /// Write server options into RustDesk2.toml
+    Set(ServerSetArgs),
+    /// Generate a RustDesk config string from server options
+    Encode(ServerEncodeArgs),
+    /// Decode a RustDesk config string or installer file name
+    Decode {
+        /// Config string, or an installer file name containing one
+        config_string: String,
+    },
+}
+
+/// 自建服务器选项参数
+#[derive(Args)]
+pub struct ServerOptionArgs {
+    /// ID server (custom-rendezvous-server) as host or host:port
+    #[clap(long, value_name = "HOST")]
+    pub id_server: Option<String>,
+
+    /// Relay server (relay-server) as host or host:port
+    #[clap(long, value_name = "HOST")]
+    pub relay_server: Option<String>,
+
+    /// API server (api-server) as http(s)://host[:port]
+    #[clap(long, value_name = "URL")]
+    pub api_server: Option<String>,
+
+    /// Public key of the server (key), base64 encoded
+    #[clap(long)]
+    pub key: Option<String>,
+}
+
+impl ServerOptionArgs {
+    /// 转换为服务器设置
+    pub fn settings(&self) -> ServerSettings {
+        ServerSettings {
+            rendezvous_server: self.id_server.clone(),
+            relay_server: self.relay_server.clone(),
+            api_server: self.api_server.clone(),
+            key: self.key.clone(),
+        }
     }
+}
+
+#[derive(Args)]
+pub struct ServerSetArgs {
+    #[clap(flatten)]
+    pub options: ServerOptionArgs,
 
-    let uuid_option = cli.uuid.as_deref();
-    let has_id = cli.id.is_some();
-    let has_eid = cli.eid.is_some();
+    /// RustDesk2.toml to modify (auto-detected if omitted, repeatable)
+    #[clap(long = "config", value_name = "PATH")]
+    pub configs: Vec<PathBuf>,
 
-    if !has_id && !has_eid && uuid_option.is_none() {
-        show_interactive_menu(&cli.lang);
-        return;
+    /// Show the changes without writing them
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// Do not keep a backup of the original file
+    #[clap(long)]
+    pub no_backup: bool,
+}
+
+#[derive(Args)]
+pub struct ServerEncodeArgs {
+    #[clap(flatten)]
+    pub options: ServerOptionArgs,
+
+    /// RustDesk2.toml to read when no server options are given (auto-detected if omitted)
+    #[clap(long = "config", value_name = "PATH")]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct CheckIdArgs {
+    /// Custom ID to look up
+    pub id: String,
+
+    /// ID server as host or host:port (read from RustDesk2.toml if omitted)
+    #[clap(long, value_name = "HOST")]
+    pub id_server: Option<String>,
+
+    /// Public key of the server (read from RustDesk2.toml if omitted)
+    #[clap(long)]
+    pub key: Option<String>,
+
+    /// RustDesk2.toml to read the server options from (auto-detected if omitted)
+    #[clap(long = "config", value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Seconds to wait for each reply from the server
+    #[clap(long, value_name = "SECS", default_value = "5")]
+    pub timeout: u64,
+}
+
+#[derive(Args)]
+pub struct RegisterArgs {
+    /// Custom ID to register (decrypted from RustDesk.toml if omitted)
+    pub id: Option<String>,
+
+    #[clap(flatten)]
+    pub uuid: UuidArg,
+
+    /// Public key of the machine, base64 encoded (read from key_pair in RustDesk.toml if omitted)
+    #[clap(long, value_name = "BASE64")]
+    pub pk: Option<String>,
+
+    /// RustDesk.toml to read the ID and public key from (auto-detected if omitted)
+    #[clap(long = "config", value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// ID server as host or host:port (read from RustDesk2.toml if omitted)
+    #[clap(long, value_name = "HOST")]
+    pub id_server: Option<String>,
+
+    /// RustDesk2.toml to read the ID server from (auto-detected if omitted)
+    #[clap(long, value_name = "PATH")]
+    pub server_config: Option<PathBuf>,
+
+    /// Seconds to wait for the reply from the server
+    #[clap(long, value_name = "SECS", default_value = "5")]
+    pub timeout: u64,
+}
+
+#[derive(Args)]
+pub struct AutoIdArgs {
+    /// MAC address of the machine's first network interface, e.g. 00:11:22:33:44:55
+    #[clap(required = true, value_name = "MAC")]
+    pub mac: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct BatchArgs {
+    /// Input file with one value per line, optionally followed by `,UUID` (`-` for stdin)
+    pub input: PathBuf,
+
+    /// Decrypt enc_id values instead of encrypting custom IDs
+    #[clap(long)]
+    pub decrypt: bool,
+
+    /// Write the CSV result to this new file instead of stdout
+    #[clap(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Encrypt the CSV result with a passphrase, to be opened with unseal
+    #[clap(long)]
+    pub seal: bool,
+
+    /// Passphrase for --seal (prompted if omitted)
+    #[clap(long, env = PASSPHRASE_ENV, hide_env_values = true, value_parser)]
+    pub passphrase: Option<SecretString>,
+
+    #[clap(flatten)]
+    pub uuid: UuidArg,
+}
+
+#[derive(Args)]
+pub struct UnsealArgs {
+    /// File created by batch --seal or export
+    #[clap(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Write the decrypted content to this new file instead of stdout
+    #[clap(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Passphrase protecting the file (prompted if omitted)
+    #[clap(long, env = PASSPHRASE_ENV, hide_env_values = true, value_parser)]
+    pub passphrase: Option<SecretString>,
+}
+
+#[derive(Args)]
+pub struct UuidArgs {
+    /// UUIDs to check instead of the detected machine UUID
+    #[clap(value_name = "UUID", value_parser)]
+    pub uuids: Vec<SecretString>,
+}
+
+#[derive(Args)]
+pub struct ScriptArgs {
+    /// Custom ID to apply on the target machine
+    #[clap(long)]
+    pub id: String,
+
+    /// UUID of the target machine
+    #[clap(short, long, value_parser)]
+    pub uuid: SecretString,
+
+    /// Script format
+    #[clap(long, value_enum, default_value = "powershell")]
+    pub format: ScriptFormat,
+
+    /// Write the script to this new file instead of stdout
+    #[clap(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Address to listen on
+    #[clap(long, value_name = "ADDR", default_value = DEFAULT_BIND)]
+    pub bind: SocketAddr,
+
+    /// Token clients must send as `Authorization: Bearer <TOKEN>` (generated if omitted)
+    #[clap(long, env = TOKEN_ENV, hide_env_values = true, value_parser)]
+    pub token: Option<SecretString>,
+
+    /// ID server that /check-id asks, as host or host:port (read from RustDesk2.toml if omitted)
+    #[clap(long, value_name = "HOST")]
+    pub id_server: Option<String>,
+
+    /// Public key of the ID server (read from RustDesk2.toml if omitted)
+    #[clap(long)]
+    pub key: Option<String>,
+
+    /// RustDesk2.toml to read the server options from (auto-detected if omitted)
+    #[clap(long = "config", value_name = "PATH")]
+    pub config: Option<PathBuf>,
+}
+
+/// 命令名称，用于补全脚本和手册页
+const BIN_NAME: &str = "custom-rustdesk";
+
+pub fn run() -> ExitCode {
+    // 帮助文本在解析参数时生成，因此需要先确定语言
+    let locale = resolve_locale(scan_lang(std::env::args()).as_deref());
+    rust_i18n::set_locale(&locale);
+    let matches = localized_command().get_matches();
+    let cli = match Cli::from_arg_matches(&matches) {
+        Ok(cli) => cli,
+        Err(e) => e.exit(),
+    };
+    if cli.lang.as_deref() == Some(LIST_LOCALES) {
+        show_locales(&locale);
+        return ExitCode::SUCCESS;
     }
+    set_reveal_uuid(cli.show_uuid);
 
-    let uuid = match uuid_option {
-        Some(u) => u.to_string(),
-        None => match get_uuid_with_confirmation() {
-            UuidResult::Success(uuid) => uuid,
-            UuidResult::Cancelled => {
-                println!("{}", t!("operation_cancelled"));
-                return;
-            }
-            UuidResult::Error => {
-                println!("{}", t!("error_uuid_required"));
-                println!("{}", t!("help_prompt"));
-                return;
-            }
-        },
+    let clipboard_mode = if cli.no_clipboard {
+        ClipboardMode::None
+    } else {
+        cli.clipboard
+    };
+    match create_backend(clipboard_mode, cli.clipboard_file.clone()) {
+        Ok(backend) => set_backend(backend),
+        Err(error_msg) => {
+            display_error(&error_msg);
+            return ExitCode::FAILURE;
+        }
+    }
+    set_clear_after(cli.clipboard_clear_after.map(Duration::from_secs));
+
+    let code = run_command(cli);
+    wait_for_pending_clear();
+    code
+}
+
+/// 确定界面语言：优先使用 `--lang`，否则从环境中检测，都不可用时使用英文
+///
+/// `--lang` 或语言环境变量指定了没有翻译的语言时显示警告，操作系统设置不受支持时静默使用英文。
+fn resolve_locale(requested: Option<&str>) -> String {
+    let available = available_locales();
+    let unsupported = |lang: &str| {
+        rust_i18n::set_locale(DEFAULT_LOCALE);
+        eprintln!(
+            "{}",
+            t!(
+                "unsupported_locale_warning",
+                lang = lang,
+                available = available.join(", ")
+            )
+        );
+        DEFAULT_LOCALE.to_string()
     };
+    match requested {
+        Some(lang) if lang != LIST_LOCALES => {
+            normalize_locale(lang, &available).unwrap_or_else(|| unsupported(lang))
+        }
+        _ => match env_locale() {
+            Some(tag) => normalize_locale(&tag, &available).unwrap_or_else(|| unsupported(&tag)),
+            None => detect_locale(&available).unwrap_or_else(|| DEFAULT_LOCALE.to_string()),
+        },
+    }
+}
+
+/// 列出可用的语言，标记当前使用的语言
+fn show_locales(current: &str) {
+    println!("{}", t!("available_locales_title"));
+    for locale in available_locales() {
+        let marker = if locale == current { "*" } else { " " };
+        println!(
+            " {} {:<6} {}",
+            marker,
+            locale,
+            t!("language_name", locale = locale)
+        );
+    }
+}
 
-    run_with_uuid(&cli, &uuid);
+/// 在解析参数之前从命令行中找出 `--lang`/`-l` 的值
+fn scan_lang(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let mut lang = None;
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        } else if arg == "--lang" || arg == "-l" {
+            lang = args.next();
+        } else if let Some(value) = arg.strip_prefix("--lang=") {
+            lang = Some(value.to_string());
+        } else if let Some(value) = arg.strip_prefix("-l").filter(|v| !v.is_empty()) {
+            lang = Some(value.trim_start_matches('=').to_string());
+        }
+    }
+    lang
 }
 
-fn run_with_uuid(cli: &Cli, uuid: &str) {
-    if let Some(ref custom_id) = cli.id {
-        let result = perform_encrypt(custom_id, uuid);
-        match result {
-            crate::core::handler::EncryptResult::Success { .. } => {
-                display_encrypt_success(&result);
+fn run_command(cli: Cli) -> ExitCode {
+    let command = match cli.command {
+        Some(command) => command,
+        None => match legacy_command(cli.id, cli.eid, cli.uuid) {
+            Some(command) => {
+                eprintln!("{}", t!("deprecated_flags_warning"));
+                command
             }
-            crate::core::handler::EncryptResult::Error(error_msg) => {
-                display_error(&error_msg);
+            None => {
+                show_interactive_menu();
+                return ExitCode::SUCCESS;
             }
+        },
+    };
+
+    match command {
+        Commands::Encrypt(args) => run_encrypt(&args),
+        Commands::Decrypt(args) => run_decrypt(&args),
+        Commands::Apply(args) => run_apply(&args),
+        Commands::Ensure(args) => run_ensure(&args),
+        Commands::Rekey(args) => run_rekey(&args),
+        Commands::Export(args) => run_export(&args),
+        Commands::Import(args) => run_import(&args),
+        Commands::Inspect(args) => run_inspect(&args),
+        Commands::CheckId(args) => run_check_id(&args),
+        Commands::Register(args) => run_register(&args),
+        Commands::AutoId(args) => run_auto_id(&args),
+        Commands::Password(command) => run_password(&command),
+        Commands::Server(command) => run_server(&command),
+        Commands::Batch(args) => run_batch(&args),
+        Commands::Unseal(args) => run_unseal(&args),
+        Commands::Script(args) => run_script(&args),
+        Commands::Serve(args) => run_serve(&args),
+        Commands::Uuid(args) => run_uuid(&args),
+        Commands::Tui(args) => run_tui(&args),
+        Commands::Completions { shell } => {
+            let mut command = localized_command();
+            clap_complete::generate(shell, &mut command, BIN_NAME, &mut io::stdout());
+            ExitCode::SUCCESS
         }
-    } else if let Some(ref enc_id) = cli.eid {
-        let result = perform_decrypt(enc_id, uuid);
-        match result {
-            crate::core::handler::DecryptResult::Success { .. } => {
-                display_decrypt_success(&result);
-            }
-            crate::core::handler::DecryptResult::Error(error_msg) => {
-                display_error(&error_msg);
+        Commands::Man => {
+            let man = clap_mangen::Man::new(localized_command().name(BIN_NAME));
+            match man.render(&mut io::stdout()) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(e) => {
+                    display_error(&format!("{}: {}", t!("io_error"), e));
+                    ExitCode::FAILURE
+                }
             }
         }
     }
 }
+
+/// 将旧版的 `--id`/`--eid`/`--uuid` 参数转换为对应的子命令
+fn legacy_command(
+    id: Option<String>,
+    eid: Option<String>,
+    uuid: Option<SecretString>,
+) -> Option<Commands> {
+    let uuid = UuidArg {
+        uuid,
+        uuid_form: None,
+    };
+    match (id, eid) {
+        (Some(id), _) => Some(Commands::Encrypt(EncryptArgs { id, uuid })),
+        (None, Some(enc_id)) => Some(Commands::Decrypt(DecryptArgs { enc_id, uuid })),
+        (None, None) => None,
+    }
+}
@@ -0,0 +1,1440 @@
+//! 子命令实现模块
+//!
+//! 每个子命令负责解析 UUID、调用核心模块完成操作并显示结果，返回进程退出码。
+
+use crate::app::cli::{
+    ApplyArgs, AutoIdArgs, BatchArgs, CheckIdArgs, DecryptArgs, EncryptArgs, EnsureArgs,
+    ExportArgs, ImportArgs, InspectArgs, PasswordCommand, RegisterArgs, RekeyArgs, ScriptArgs,
+    ServeArgs, ServerCommand, ServerEncodeArgs, ServerSetArgs, UnsealArgs, UuidArgs,
+};
+use crate::app::input::prompt_secret;
+use crate::app::uuid::{given_uuid, resolve_uuid, resolve_uuid_arg, uuid_source};
+use crate::core::api::{generate_token, serve, IdServer};
+use crate::core::auto_id::{auto_id, parse_mac_address, MIN_ID_LEN};
+use crate::core::batch::{csv_row, parse_batch, SEALED_FORMAT};
+use crate::core::bundle::{open_identity, seal_identity, Identity};
+use crate::core::config::{
+    default_config_paths, default_paths, detect_config_files, detect_files, ConfigFile,
+    ConfigUpdate, CONFIG_FILE_NAME, ENC_ID_KEY, ENC_VERSION_PREFIX, KEY_PAIR_KEY,
+    OPTIONS_FILE_NAME, PASSWORD_KEY,
+};
+use crate::core::deploy::{render_script, ScriptFormat};
+use crate::core::ensure::{ensure_config, EnsureReport, EnsureStatus};
+use crate::core::error::{AppError, AppResult};
+use crate::core::crypto::KEY_BYTES;
+use crate::core::handler::{
+    decrypt_value, display_config_string, display_decrypt_success, display_encrypt_success,
+    display_error, display_password_decrypt_success, display_password_encrypt_success,
+    perform_decrypt, perform_decrypt_password, perform_encrypt, perform_encrypt_password,
+    DecryptResult, EncryptResult,
+};
+use crate::core::inspect::{
+    inspect_config_file, InspectReport, KeyOrigin, KeyReport, MachineUuidReport, PasswordStatus,
+};
+use crate::core::options::{ServerOption, ServerSettings};
+use crate::core::rekey::{plan_rekey, RekeyStatus};
+use crate::core::rendezvous::{check_id, register_pk, IdStatus};
+use crate::core::sealed;
+use crate::core::secret::{is_uuid_revealed, SecretString};
+use crate::core::uuid_key::{convert_uuid, display_key, same_key, UuidForm, UuidKey};
+use crate::core::validation::{
+    custom_id_warnings, validate_custom_id, validate_public_key, validate_uuid,
+};
+use rust_i18n::t;
+use sodiumoxide::base64;
+use sodiumoxide::crypto::sign;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+use zeroize::Zeroizing;
+
+/// 将操作结果转换为退出码
+fn exit_code(success: bool) -> ExitCode {
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// 加密自定义 ID
+pub fn run_encrypt(args: &EncryptArgs) -> ExitCode {
+    let Some(uuid) = resolve_uuid_arg(&args.uuid) else {
+        return ExitCode::FAILURE;
+    };
+
+    let result = perform_encrypt(&args.id, &uuid);
+    match result {
+        EncryptResult::Success { .. } => {
+            display_encrypt_success(&result);
+            ExitCode::SUCCESS
+        }
+        EncryptResult::Error(error_msg) => {
+            display_error(&error_msg);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 解密加密 ID
+pub fn run_decrypt(args: &DecryptArgs) -> ExitCode {
+    let Some(uuid) = resolve_uuid_arg(&args.uuid) else {
+        return ExitCode::FAILURE;
+    };
+
+    let result = perform_decrypt(&args.enc_id, &uuid);
+    match result {
+        DecryptResult::Success { .. } => {
+            display_decrypt_success(&result);
+            ExitCode::SUCCESS
+        }
+        DecryptResult::Error(error_msg) => {
+            display_error(&error_msg);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 确定要处理的 RustDesk.toml：优先使用参数指定的文件，否则自动检测
+pub fn resolve_config_paths(configs: &[PathBuf]) -> Option<Vec<PathBuf>> {
+    resolve_paths(configs, CONFIG_FILE_NAME)
+}
+
+/// 确定要处理的 RustDesk2.toml：优先使用参数指定的文件，否则自动检测
+pub fn resolve_options_paths(configs: &[PathBuf]) -> Option<Vec<PathBuf>> {
+    resolve_paths(configs, OPTIONS_FILE_NAME)
+}
+
+/// 确定要处理的配置文件，找不到时显示查找过的位置并返回 `None`
+fn resolve_paths(configs: &[PathBuf], file_name: &str) -> Option<Vec<PathBuf>> {
+    if !configs.is_empty() {
+        return Some(configs.to_vec());
+    }
+
+    let detected = detect_files(file_name);
+    if detected.is_empty() {
+        println!("{}", t!("no_config_found", file = file_name));
+        println!("{}", t!("searched_config_paths"));
+        for path in default_paths(file_name) {
+            println!("   - {}", path.display());
+        }
+        return None;
+    }
+    Some(detected)
+}
+
+/// 将新的 ID 和/或永久密码写入配置文件
+pub fn run_apply(args: &ApplyArgs) -> ExitCode {
+    let Some(paths) = resolve_config_paths(&args.configs) else {
+        return ExitCode::FAILURE;
+    };
+    let Some(uuid) = resolve_uuid_arg(&args.uuid) else {
+        return ExitCode::FAILURE;
+    };
+    let Some(updates) = encrypt_updates(args.id.as_deref(), args.password.as_ref(), &uuid) else {
+        return ExitCode::FAILURE;
+    };
+
+    exit_code(apply_updates(&paths, &updates, args.dry_run, !args.no_backup))
+}
+
+/// 加密要写入配置文件的字段，失败时显示错误并返回 `None`
+pub fn encrypt_updates(
+    custom_id: Option<&str>,
+    password: Option<&SecretString>,
+    uuid: &SecretString,
+) -> Option<Vec<ConfigUpdate>> {
+    let mut updates = Vec::new();
+    if let Some(custom_id) = custom_id {
+        match perform_encrypt(custom_id, uuid) {
+            EncryptResult::Success { encrypted_id, .. } => {
+                updates.push(ConfigUpdate::top_level(
+                    ENC_ID_KEY,
+                    format!("{}{}", ENC_VERSION_PREFIX, encrypted_id),
+                ));
+            }
+            EncryptResult::Error(error_msg) => {
+                display_error(&error_msg);
+                return None;
+            }
+        }
+    }
+    if let Some(password) = password {
+        match perform_encrypt_password(password, uuid) {
+            Ok(enc_password) => updates.push(ConfigUpdate::top_level(PASSWORD_KEY, enc_password)),
+            Err(e) => {
+                display_error(&e.to_string());
+                return None;
+            }
+        }
+    }
+    Some(updates)
+}
+
+/// 将字段写入每个配置文件，全部成功时返回 `true`
+pub fn apply_updates(
+    paths: &[PathBuf],
+    updates: &[ConfigUpdate],
+    dry_run: bool,
+    backup: bool,
+) -> bool {
+    let mut success = true;
+    for path in paths {
+        println!();
+        println!("{}", t!("config_file_label", path = path.display()));
+        if let Err(e) = apply_to_config(path, updates, dry_run, backup) {
+            display_error(&e.to_string());
+            success = false;
+        }
+    }
+
+    if success && !dry_run {
+        println!();
+        println!("{}", t!("restart_service_prompt"));
+    }
+    success
+}
+
+/// 修改单个配置文件：显示差异，并在非预演模式下备份和写入
+fn apply_to_config(
+    path: &Path,
+    updates: &[ConfigUpdate],
+    dry_run: bool,
+    backup: bool,
+) -> AppResult<()> {
+    let mut config = ConfigFile::load(path)?;
+    for update in updates {
+        config.apply(update)?;
+    }
+    save_config(&config, dry_run, backup)
+}
+
+/// 显示已修改的配置文件的差异，并在非预演模式下备份和写入
+fn save_config(config: &ConfigFile, dry_run: bool, backup: bool) -> AppResult<()> {
+    if !config.is_modified() {
+        println!("{}", t!("config_no_changes"));
+        return Ok(());
+    }
+
+    for line in config.diff() {
+        println!("   {}", line.redact(&[KEY_PAIR_KEY]));
+    }
+
+    if dry_run {
+        println!("{}", t!("config_dry_run"));
+        return Ok(());
+    }
+
+    if let Some(backup_path) = config.save(backup)? {
+        println!(
+            "{}",
+            t!("config_backup_saved", path = backup_path.display())
+        );
+    }
+    println!("{}", t!("config_updated", path = config.path.display()));
+    Ok(())
+}
+
+/// 从本机的配置中导出身份，保存为用口令加密的迁移包
+pub fn run_export(args: &ExportArgs) -> ExitCode {
+    if args.bundle.exists() {
+        display_error(&t!("bundle_exists", path = args.bundle.display()));
+        return ExitCode::FAILURE;
+    }
+    let configs: Vec<PathBuf> = args.config.iter().cloned().collect();
+    let Some(paths) = resolve_config_paths(&configs) else {
+        return ExitCode::FAILURE;
+    };
+    let options_path = args
+        .server_config
+        .clone()
+        .or_else(|| detect_files(OPTIONS_FILE_NAME).into_iter().next());
+    let Some(uuid) = resolve_uuid_arg(&args.uuid) else {
+        return ExitCode::FAILURE;
+    };
+
+    println!("{}", t!("config_file_label", path = paths[0].display()));
+    let identity = ConfigFile::load(&paths[0]).and_then(|config| {
+        let options = match &options_path {
+            Some(path) => {
+                println!("{}", t!("config_file_label", path = path.display()));
+                Some(ConfigFile::load(path)?)
+            }
+            None => None,
+        };
+        Identity::from_configs(&config, options.as_ref(), &uuid)
+    });
+    let identity = match identity {
+        Ok(identity) => identity,
+        Err(e) => {
+            display_error(&e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+    display_identity(&identity);
+
+    let Some(passphrase) = resolve_passphrase(args.passphrase.as_ref(), true) else {
+        return ExitCode::FAILURE;
+    };
+    let result = seal_identity(&identity, &passphrase).and_then(|bundle| {
+        write_private_file(&args.bundle, bundle.as_bytes())
+            .map_err(|e| AppError::IoError(format!("{}: {}", args.bundle.display(), e)))
+    });
+    match result {
+        Ok(()) => {
+            println!("{}", t!("bundle_exported", path = args.bundle.display()));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            display_error(&e.to_string());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 打开迁移包，用本机的 UUID 重新加密后写入配置文件
+pub fn run_import(args: &ImportArgs) -> ExitCode {
+    let content = match fs::read_to_string(&args.bundle) {
+        Ok(content) => content,
+        Err(e) => {
+            display_error(&format!("{}: {}: {}", t!("io_error"), args.bundle.display(), e));
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(passphrase) = resolve_passphrase(args.passphrase.as_ref(), false) else {
+        return ExitCode::FAILURE;
+    };
+    let identity = match open_identity(&content, &passphrase) {
+        Ok(identity) => identity,
+        Err(e) => {
+            display_error(&e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+    display_identity(&identity);
+
+    let Some(paths) = resolve_config_paths(&args.configs) else {
+        return ExitCode::FAILURE;
+    };
+    let options_paths = if identity.options.is_empty() {
+        Vec::new()
+    } else {
+        let Some(paths) = resolve_options_paths(&args.server_configs) else {
+            return ExitCode::FAILURE;
+        };
+        paths
+    };
+    let Some(uuid) = resolve_uuid_arg(&args.uuid) else {
+        return ExitCode::FAILURE;
+    };
+    let updates = match identity.config_updates(&uuid) {
+        Ok(updates) => updates,
+        Err(e) => {
+            display_error(&e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let backup = !args.no_backup;
+    let mut success = true;
+    for path in &paths {
+        println!();
+        println!("{}", t!("config_file_label", path = path.display()));
+        let result = ConfigFile::load(path).and_then(|mut config| {
+            for update in &updates {
+                config.apply(update)?;
+            }
+            identity.apply_key_pair(&mut config)?;
+            save_config(&config, args.dry_run, backup)
+        });
+        if let Err(e) = result {
+            display_error(&e.to_string());
+            success = false;
+        }
+    }
+    let options_updates = identity.options_updates();
+    for path in &options_paths {
+        println!();
+        println!("{}", t!("config_file_label", path = path.display()));
+        if let Err(e) = apply_to_config(path, &options_updates, args.dry_run, backup) {
+            display_error(&e.to_string());
+            success = false;
+        }
+    }
+
+    if success && !args.dry_run {
+        println!();
+        println!("{}", t!("restart_service_prompt"));
+    }
+    exit_code(success)
+}
+
+/// 显示迁移包中包含的内容
+fn display_identity(identity: &Identity) {
+    println!("{}", t!("bundle_contents"));
+    if let Some(id) = &identity.id {
+        println!("{}", t!("bundle_item_id", id = id));
+    }
+    if identity.password.is_some() {
+        println!("{}", t!("bundle_item_password"));
+    }
+    if identity.has_key_pair() {
+        println!("{}", t!("bundle_item_key_pair"));
+    }
+    if !identity.options.is_empty() {
+        println!(
+            "{}",
+            t!("bundle_item_options", count = identity.options.len())
+        );
+    }
+}
+
+/// 取得口令：优先使用参数或环境变量，否则提示输入，加密时需要再次确认
+///
+/// 无论来源如何，口令都原样使用，不去除首尾空白。
+fn resolve_passphrase(given: Option<&SecretString>, confirm: bool) -> Option<SecretString> {
+    let passphrase = match given {
+        Some(passphrase) => passphrase.clone(),
+        None => {
+            let passphrase = prompt_secret(&t!("passphrase_prompt"))?;
+            if confirm {
+                let again = prompt_secret(&t!("passphrase_confirm"))?;
+                if again != passphrase {
+                    display_error(&t!("passphrase_mismatch"));
+                    return None;
+                }
+            }
+            passphrase
+        }
+    };
+    if passphrase.expose().is_empty() {
+        display_error(&t!("passphrase_empty"));
+        return None;
+    }
+    Some(passphrase)
+}
+
+/// 创建新文件，不覆盖已有文件，并且只允许当前用户读写
+fn write_private_file(path: &Path, content: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(content)
+}
+
+/// 用旧 UUID 解密配置文件中的加密字段，再用新 UUID 重新加密
+pub fn run_rekey(args: &RekeyArgs) -> ExitCode {
+    if let Err(e) = validate_uuid(args.from_uuid.expose()) {
+        display_error(&e.to_string());
+        return ExitCode::FAILURE;
+    }
+    let Some(paths) = resolve_config_paths(&args.configs) else {
+        return ExitCode::FAILURE;
+    };
+    let Some(to_uuid) = resolve_uuid(args.to_uuid.as_ref()) else {
+        return ExitCode::FAILURE;
+    };
+    if same_key(args.from_uuid.expose(), to_uuid.expose()) {
+        println!("{}", t!("rekey_same_key", max = KEY_BYTES));
+    }
+
+    let mut success = true;
+    for path in &paths {
+        println!();
+        println!("{}", t!("config_file_label", path = path.display()));
+        if let Err(e) = rekey_config(path, &args.from_uuid, &to_uuid, args.dry_run, !args.no_backup)
+        {
+            display_error(&e.to_string());
+            success = false;
+        }
+    }
+
+    if success && !args.dry_run {
+        println!();
+        println!("{}", t!("restart_service_prompt"));
+    }
+    exit_code(success)
+}
+
+/// 重新加密单个配置文件，显示每个加密字段的处理结果
+fn rekey_config(
+    path: &Path,
+    from_uuid: &SecretString,
+    to_uuid: &SecretString,
+    dry_run: bool,
+    backup: bool,
+) -> AppResult<()> {
+    // 计划和修改基于同一次读取的内容，避免写入期间被改动的文件时使用过时的计划
+    let mut config = ConfigFile::load(path)?;
+    let plan = plan_rekey(&config, from_uuid, to_uuid)?;
+    if plan.fields.is_empty() {
+        println!("{}", t!("rekey_no_encrypted_fields"));
+        return Ok(());
+    }
+    for (key, status) in &plan.fields {
+        let message = match status {
+            RekeyStatus::Rekeyed => "rekey_field_rekeyed",
+            RekeyStatus::AlreadyRekeyed => "rekey_field_already_rekeyed",
+        };
+        println!("{}", t!(message, key = key));
+    }
+    for update in &plan.updates {
+        config.apply(update)?;
+    }
+    save_config(&config, dry_run, backup)
+}
+
+/// 确保配置文件中保存的是给定的 ID，以 JSON 报告是否有改动
+///
+/// 供配置管理工具调用，因此不会提示确认 UUID：未指定 `--uuid` 时直接使用检测到的机器 UUID。
+pub fn run_ensure(args: &EnsureArgs) -> ExitCode {
+    let report = build_ensure_report(args);
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            display_error(&e.to_string());
+            return ExitCode::FAILURE;
+        }
+    }
+    exit_code(!report.has_errors())
+}
+
+fn build_ensure_report(args: &EnsureArgs) -> EnsureReport {
+    let failed = |error: String| EnsureReport::failed(&args.id, args.dry_run, error);
+
+    let uuid = match given_uuid(&args.uuid) {
+        Ok(Some(uuid)) => uuid,
+        Ok(None) => match machine_uid::get() {
+            Ok(uuid) => SecretString::new(uuid),
+            Err(e) => return failed(t!("ensure_uuid_unavailable", error = e)),
+        },
+        Err(e) => return failed(e.to_string()),
+    };
+    let encrypted_id = match perform_encrypt(&args.id, &uuid) {
+        EncryptResult::Success { encrypted_id, .. } => encrypted_id,
+        EncryptResult::Error(error_msg) => return failed(error_msg),
+    };
+    let enc_id = format!("{}{}", ENC_VERSION_PREFIX, encrypted_id);
+
+    let paths = if args.configs.is_empty() {
+        detect_config_files()
+    } else {
+        args.configs.clone()
+    };
+    if paths.is_empty() {
+        return failed(t!("ensure_no_config_found", file = CONFIG_FILE_NAME));
+    }
+
+    let configs: Vec<_> = paths
+        .iter()
+        .map(|path| {
+            ensure_config(path, &args.id, &enc_id, &uuid, args.dry_run, !args.no_backup)
+        })
+        .collect();
+    EnsureReport {
+        changed: configs
+            .iter()
+            .any(|config| config.status == EnsureStatus::Changed),
+        id: args.id.clone(),
+        dry_run: args.dry_run,
+        configs,
+        error: None,
+    }
+}
+
+/// 查看或修改 RustDesk2.toml 中的服务器选项
+pub fn run_server(command: &ServerCommand) -> ExitCode {
+    match command {
+        ServerCommand::Show { configs } => run_server_show(configs),
+        ServerCommand::Set(args) => run_server_set(args),
+        ServerCommand::Encode(args) => run_server_encode(args),
+        ServerCommand::Decode { config_string } => run_server_decode(config_string),
+    }
+}
+
+/// 显示每个配置文件中的服务器选项
+fn run_server_show(configs: &[PathBuf]) -> ExitCode {
+    let Some(paths) = resolve_options_paths(configs) else {
+        return ExitCode::FAILURE;
+    };
+
+    let mut success = true;
+    for path in &paths {
+        println!();
+        println!("{}", t!("config_file_label", path = path.display()));
+        match ConfigFile::load(path) {
+            Ok(config) => {
+                display_server_settings(&ServerSettings::from_config(&config));
+            }
+            Err(e) => {
+                display_error(&e.to_string());
+                success = false;
+            }
+        }
+    }
+    exit_code(success)
+}
+
+/// 验证服务器选项并写入配置文件
+fn run_server_set(args: &ServerSetArgs) -> ExitCode {
+    let settings = args.options.settings();
+    if settings.is_empty() {
+        display_error(&t!("server_nothing_error"));
+        return ExitCode::FAILURE;
+    }
+    let updates = match settings.to_updates() {
+        Ok(updates) => updates,
+        Err(e) => {
+            display_error(&e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(paths) = resolve_options_paths(&args.configs) else {
+        return ExitCode::FAILURE;
+    };
+
+    exit_code(apply_updates(&paths, &updates, args.dry_run, !args.no_backup))
+}
+
+/// 根据参数或 RustDesk2.toml 中的服务器选项生成配置字符串
+fn run_server_encode(args: &ServerEncodeArgs) -> ExitCode {
+    let mut settings = args.options.settings();
+    if settings.is_empty() {
+        let configs: Vec<PathBuf> = args.config.iter().cloned().collect();
+        let Some(paths) = resolve_options_paths(&configs) else {
+            return ExitCode::FAILURE;
+        };
+        println!("{}", t!("config_file_label", path = paths[0].display()));
+        match ConfigFile::load(&paths[0]) {
+            Ok(config) => settings = ServerSettings::from_config(&config),
+            Err(e) => {
+                display_error(&e.to_string());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if settings.get(ServerOption::RendezvousServer).is_none() {
+        display_error(&t!("server_id_server_required"));
+        return ExitCode::FAILURE;
+    }
+    if let Err(e) = settings.to_updates() {
+        display_error(&e.to_string());
+        return ExitCode::FAILURE;
+    }
+
+    display_config_string(&settings.to_config_string());
+    ExitCode::SUCCESS
+}
+
+/// 确定要连接的 ID 服务器和公钥，未通过参数给出时从 RustDesk2.toml 读取
+fn resolve_server_settings(
+    id_server: Option<&str>,
+    key: Option<&str>,
+    config: Option<&PathBuf>,
+) -> Option<ServerSettings> {
+    let mut settings = ServerSettings {
+        rendezvous_server: id_server.map(str::to_string),
+        key: key.map(str::to_string),
+        ..ServerSettings::default()
+    };
+    if settings.rendezvous_server.is_none() {
+        let configs: Vec<PathBuf> = config.into_iter().cloned().collect();
+        let paths = resolve_options_paths(&configs)?;
+        println!("{}", t!("config_file_label", path = paths[0].display()));
+        let stored = match ConfigFile::load(&paths[0]) {
+            Ok(config) => ServerSettings::from_config(&config),
+            Err(e) => {
+                display_error(&e.to_string());
+                return None;
+            }
+        };
+        settings.rendezvous_server = stored.rendezvous_server;
+        settings.key = settings.key.or(stored.key);
+    }
+
+    match settings.get(ServerOption::RendezvousServer) {
+        Some(server) => match ServerOption::RendezvousServer.validate(server) {
+            Ok(()) => Some(settings),
+            Err(e) => {
+                display_error(&e.to_string());
+                None
+            }
+        },
+        None => {
+            display_error(&t!("server_id_server_required"));
+            None
+        }
+    }
+}
+
+/// 查询自定义 ID 是否已在 ID 服务器上被占用，只有未被占用时才返回成功
+pub fn run_check_id(args: &CheckIdArgs) -> ExitCode {
+    if let Err(e) = validate_custom_id(&args.id) {
+        display_error(&e.to_string());
+        return ExitCode::FAILURE;
+    }
+    let Some(settings) = resolve_server_settings(
+        args.id_server.as_deref(),
+        args.key.as_deref(),
+        args.config.as_ref(),
+    ) else {
+        return ExitCode::FAILURE;
+    };
+    let server = settings.rendezvous_server.as_deref().unwrap_or_default();
+    let key = settings.key.as_deref().unwrap_or_default();
+
+    match check_id(server, &args.id, key, Duration::from_secs(args.timeout)) {
+        Ok(status) => {
+            let message = match status {
+                IdStatus::Available => "check_id_available",
+                IdStatus::Offline => "check_id_offline",
+                IdStatus::Online => "check_id_online",
+            };
+            println!("{}", t!(message, id = args.id, server = server));
+            exit_code(status == IdStatus::Available)
+        }
+        Err(e) => {
+            display_error(&e.to_string());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 计算 RustDesk 由 MAC 地址生成的默认 ID
+pub fn run_auto_id(args: &AutoIdArgs) -> ExitCode {
+    let mut success = true;
+    for mac in &args.mac {
+        match parse_mac_address(mac) {
+            Ok(bytes) => {
+                let id = auto_id(&bytes);
+                println!("{}", t!("auto_id_result", mac = mac.trim(), id = id));
+                if id.len() < MIN_ID_LEN {
+                    println!("{}", t!("auto_id_too_short", id = id));
+                }
+            }
+            Err(e) => {
+                display_error(&e.to_string());
+                success = false;
+            }
+        }
+    }
+    println!("{}", t!("auto_id_note"));
+    exit_code(success)
+}
+
+/// 在 ID 服务器上为本机的 UUID 和公钥注册 ID
+pub fn run_register(args: &RegisterArgs) -> ExitCode {
+    let Some(settings) =
+        resolve_server_settings(args.id_server.as_deref(), None, args.server_config.as_ref())
+    else {
+        return ExitCode::FAILURE;
+    };
+    let server = settings.rendezvous_server.as_deref().unwrap_or_default();
+    let Some(uuid) = resolve_uuid_arg(&args.uuid) else {
+        return ExitCode::FAILURE;
+    };
+    let Some((id, pk)) = resolve_registration(args, &uuid) else {
+        return ExitCode::FAILURE;
+    };
+
+    if let Err(e) = validate_custom_id(&id) {
+        display_error(&e.to_string());
+        return ExitCode::FAILURE;
+    }
+    for warning in custom_id_warnings(&id) {
+        println!("{}", t!("inspect_warning", message = warning));
+    }
+
+    // RustDesk 客户端注册时发送的是原样的机器 UUID 字符串
+    let timeout = Duration::from_secs(args.timeout);
+    match register_pk(server, &id, uuid.expose().trim().as_bytes(), &pk, timeout) {
+        Ok(()) => {
+            println!("{}", t!("register_success", id = id, server = server));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            display_error(&e.to_string());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 确定要注册的 ID 和公钥，未通过参数给出时从 RustDesk.toml 读取
+fn resolve_registration(args: &RegisterArgs, uuid: &SecretString) -> Option<(String, Vec<u8>)> {
+    let config = if args.id.is_none() || args.pk.is_none() {
+        let configs: Vec<PathBuf> = args.config.iter().cloned().collect();
+        let paths = resolve_config_paths(&configs)?;
+        println!("{}", t!("config_file_label", path = paths[0].display()));
+        match ConfigFile::load(&paths[0]) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                display_error(&e.to_string());
+                return None;
+            }
+        }
+    } else {
+        None
+    };
+
+    let id = match (&args.id, &config) {
+        (Some(id), _) => id.trim().to_string(),
+        (None, Some(config)) => {
+            let Some(enc_id) = config.get_str(ENC_ID_KEY) else {
+                display_error(&t!("register_no_enc_id", path = config.path.display()));
+                return None;
+            };
+            match decrypt_value(enc_id, uuid) {
+                Ok(id) => id.expose().to_string(),
+                Err(e) => {
+                    display_error(&e.to_string());
+                    return None;
+                }
+            }
+        }
+        (None, None) => return None,
+    };
+
+    let pk = match (&args.pk, &config) {
+        (Some(pk), _) => {
+            if let Err(e) = validate_public_key(pk) {
+                display_error(&e.to_string());
+                return None;
+            }
+            base64::decode(pk.trim(), base64::Variant::Original).ok()?
+        }
+        (None, Some(config)) => {
+            match config
+                .get_nested_bytes(KEY_PAIR_KEY, 1)
+                .filter(|pk| pk.len() == sign::PUBLICKEYBYTES)
+            {
+                Some(pk) => pk,
+                None => {
+                    display_error(&t!("register_no_key_pair", path = config.path.display()));
+                    return None;
+                }
+            }
+        }
+        (None, None) => return None,
+    };
+    Some((id, pk))
+}
+
+/// 解码配置字符串并显示其中的服务器选项
+fn run_server_decode(config_string: &str) -> ExitCode {
+    match ServerSettings::from_config_string(config_string) {
+        Ok(settings) => {
+            println!("{}", t!("config_string_decoded"));
+            display_server_settings(&settings);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            display_error(&e.to_string());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 逐项显示服务器选项，无效的值附带警告
+fn display_server_settings(settings: &ServerSettings) {
+    for option in ServerOption::ALL {
+        let value = settings.get(option);
+        println!(
+            "{}",
+            t!(
+                "server_option_value",
+                option = option.key(),
+                value = value.map_or_else(|| t!("server_option_unset"), str::to_string)
+            )
+        );
+        if let Some(Err(e)) = value.map(|value| option.validate(value)) {
+            println!("{}", t!("inspect_warning", message = e));
+        }
+    }
+}
+
+/// 生成并显示配置文件的诊断报告
+pub fn run_inspect(args: &InspectArgs) -> ExitCode {
+    let uuid = match given_uuid(&args.uuid) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            display_error(&e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+    let report = build_inspect_report(&args.configs, uuid.as_ref());
+    if args.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                display_error(&e.to_string());
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        display_inspect_report(&report);
+    }
+    exit_code(!report.has_errors())
+}
+
+/// 显示每个配置文件的诊断报告，没有错误时返回 `true`
+pub fn inspect_configs(paths: &[PathBuf], uuid: &SecretString) -> bool {
+    let report = build_inspect_report(paths, Some(uuid));
+    display_inspect_report(&report);
+    !report.has_errors()
+}
+
+/// 收集诊断报告：未指定 UUID 时使用自动检测到的机器 UUID，未指定配置文件时自动检测
+pub fn build_inspect_report(configs: &[PathBuf], uuid: Option<&SecretString>) -> InspectReport {
+    let machine = machine_uid::get().map(SecretString::new);
+    let machine_uuid = MachineUuidReport {
+        uuid: machine.as_ref().ok().map(SecretString::display_uuid),
+        source: uuid_source(),
+        error: machine.as_ref().err().map(|e| e.to_string()),
+    };
+
+    let key_uuid = uuid.or(machine.as_ref().ok());
+    let key = key_uuid.map(|key_uuid| {
+        let origin = if machine.as_ref().ok() == Some(key_uuid) {
+            KeyOrigin::Machine
+        } else {
+            KeyOrigin::Given
+        };
+        KeyReport::new(key_uuid, origin)
+    });
+
+    let auto_detected = configs.is_empty();
+    let paths = if auto_detected {
+        detect_config_files()
+    } else {
+        configs.to_vec()
+    };
+
+    InspectReport {
+        machine_uuid,
+        key,
+        auto_detected,
+        searched_paths: if auto_detected {
+            default_config_paths()
+        } else {
+            Vec::new()
+        },
+        configs: paths
+            .iter()
+            .map(|path| inspect_config_file(path, key_uuid))
+            .collect(),
+    }
+}
+
+/// 以文本形式显示诊断报告
+fn display_inspect_report(report: &InspectReport) {
+    let machine = &report.machine_uuid;
+    match (&machine.uuid, &machine.error) {
+        (Some(uuid), _) => println!("{}", t!("inspect_machine_uuid", uuid = uuid)),
+        (None, error) => println!(
+            "{}",
+            t!(
+                "inspect_machine_uuid_failed",
+                error = error.as_deref().unwrap_or_default()
+            )
+        ),
+    }
+    if machine.uuid.is_some() && !is_uuid_revealed() {
+        println!("{}", t!("uuid_masked_hint"));
+    }
+    println!("{}", t!("uuid_source_label", source = machine.source));
+
+    match &report.key {
+        None => println!("{}", t!("inspect_key_missing")),
+        Some(key) => {
+            let label = match key.origin {
+                KeyOrigin::Machine => t!("inspect_key_machine", length = key.length),
+                KeyOrigin::Given => t!("inspect_key_given", uuid = key.uuid, length = key.length),
+            };
+            println!("{}", label);
+            if key.truncated {
+                println!("{}", t!("inspect_key_truncated", max = KEY_BYTES));
+            }
+            if let Some(error) = &key.error {
+                println!("{}", t!("inspect_warning", message = error));
+            }
+        }
+    }
+
+    if report.configs.is_empty() {
+        println!();
+        println!("{}", t!("no_config_found", file = CONFIG_FILE_NAME));
+        println!("{}", t!("searched_config_paths"));
+        for path in &report.searched_paths {
+            println!("   - {}", path.display());
+        }
+    }
+
+    for config in &report.configs {
+        println!();
+        println!("{}", t!("config_file_label", path = config.path.display()));
+        if let Some(error) = &config.error {
+            display_error(error);
+            continue;
+        }
+
+        match &config.enc_id {
+            None => println!("{}", t!("inspect_enc_id_missing")),
+            Some(enc_id) => println!("{}", t!("inspect_enc_id", value = enc_id)),
+        }
+        if let Some(id) = &config.id {
+            println!("{}", t!("inspect_decrypted_id", id = id));
+        }
+        if let Some(error) = &config.id_error {
+            println!("{}", t!("inspect_id_error", error = error));
+        }
+        for warning in &config.id_warnings {
+            println!("{}", t!("inspect_warning", message = warning));
+        }
+
+        let password = match config.password {
+            Some(PasswordStatus::Missing) | None => t!("inspect_password_missing"),
+            Some(PasswordStatus::Empty) => t!("inspect_password_empty"),
+            Some(PasswordStatus::Plaintext) => t!("inspect_password_plaintext"),
+            Some(PasswordStatus::Encrypted) => t!("inspect_password_encrypted"),
+            Some(PasswordStatus::Decrypts) => t!("inspect_password_decrypts"),
+            Some(PasswordStatus::Undecryptable) => t!("inspect_password_undecryptable"),
+        };
+        println!("{}", password);
+    }
+}
+
+/// 加密或解密永久密码
+pub fn run_password(command: &PasswordCommand) -> ExitCode {
+    match command {
+        PasswordCommand::Encrypt { password, uuid } => {
+            let password = match password {
+                Some(password) => password.clone(),
+                None => match prompt_secret(&t!("enter_password")) {
+                    Some(password) => password,
+                    None => {
+                        println!();
+                        println!("{}", t!("operation_cancelled"));
+                        return ExitCode::FAILURE;
+                    }
+                },
+            };
+            let Some(uuid) = resolve_uuid_arg(uuid) else {
+                return ExitCode::FAILURE;
+            };
+            exit_code(encrypt_password(&password, &uuid))
+        }
+        PasswordCommand::Decrypt { enc_password, uuid } => {
+            let Some(uuid) = resolve_uuid_arg(uuid) else {
+                return ExitCode::FAILURE;
+            };
+            exit_code(decrypt_password(enc_password, &uuid))
+        }
+    }
+}
+
+/// 加密永久密码并显示结果，成功时返回 `true`
+pub fn encrypt_password(password: &SecretString, uuid: &SecretString) -> bool {
+    match perform_encrypt_password(password, uuid) {
+        Ok(enc_password) => {
+            display_password_encrypt_success(&enc_password);
+            true
+        }
+        Err(e) => {
+            display_error(&e.to_string());
+            false
+        }
+    }
+}
+
+/// 解密永久密码并显示结果，成功时返回 `true`
+pub fn decrypt_password(enc_password: &str, uuid: &SecretString) -> bool {
+    match perform_decrypt_password(enc_password, uuid) {
+        Ok(password) => {
+            display_password_decrypt_success(&password);
+            true
+        }
+        Err(e) => {
+            display_error(&e.to_string());
+            false
+        }
+    }
+}
+
+/// 读取批量输入，`-` 表示标准输入
+fn read_batch_input(input: &Path) -> AppResult<String> {
+    if input == Path::new("-") {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        Ok(content)
+    } else {
+        fs::read_to_string(input)
+            .map_err(|e| AppError::IoError(format!("{}: {}", input.display(), e)))
+    }
+}
+
+/// 处理批量输入中的一个值
+fn process_batch_value(
+    value: &str,
+    uuid: &SecretString,
+    decrypt: bool,
+) -> AppResult<Zeroizing<String>> {
+    if decrypt {
+        match perform_decrypt(value, uuid) {
+            DecryptResult::Success { decrypted_id, .. } => {
+                Ok(Zeroizing::new(decrypted_id.expose().to_string()))
+            }
+            DecryptResult::Error(error_msg) => Err(AppError::CryptoError(error_msg)),
+        }
+    } else {
+        match perform_encrypt(value, uuid) {
+            EncryptResult::Success { encrypted_id, .. } => Ok(Zeroizing::new(format!(
+                "{}{}",
+                ENC_VERSION_PREFIX, encrypted_id
+            ))),
+            EncryptResult::Error(error_msg) => Err(AppError::CryptoError(error_msg)),
+        }
+    }
+}
+
+/// 批量加密或解密，结果以 CSV 输出，可以用口令加密
+pub fn run_batch(args: &BatchArgs) -> ExitCode {
+    // 从标准输入读取时无法再提示输入口令
+    if args.seal && args.passphrase.is_none() && args.input == Path::new("-") {
+        display_error(&t!("batch_seal_stdin_passphrase"));
+        return ExitCode::FAILURE;
+    }
+    if let Some(path) = args.output.as_ref().filter(|path| path.exists()) {
+        display_error(&t!("output_exists", path = path.display()));
+        return ExitCode::FAILURE;
+    }
+    let passphrase = if args.seal {
+        match resolve_passphrase(args.passphrase.as_ref(), true) {
+            Some(passphrase) => Some(passphrase),
+            None => return ExitCode::FAILURE,
+        }
+    } else {
+        None
+    };
+    let content = match read_batch_input(&args.input) {
+        Ok(content) => Zeroizing::new(content),
+        Err(e) => {
+            display_error(&e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+    let entries = parse_batch(&content);
+
+    // 只有存在未指定 UUID 的行时才需要默认 UUID
+    let default_uuid = if entries.iter().any(|entry| entry.uuid.is_none()) {
+        match resolve_uuid_arg(&args.uuid) {
+            Some(uuid) => Some(uuid),
+            None => return ExitCode::FAILURE,
+        }
+    } else {
+        None
+    };
+
+    let header = if args.decrypt {
+        ["enc_id", "uuid", "custom_id"]
+    } else {
+        ["custom_id", "uuid", "enc_id"]
+    };
+    // 每一行和拼接结果都包含明文，释放时清零
+    let mut rows = vec![Zeroizing::new(csv_row(&header))];
+    let mut failed = 0;
+
+    for entry in &entries {
+        let uuid = entry.uuid.as_ref().or(default_uuid.as_ref()).unwrap();
+        match process_batch_value(&entry.value, uuid, args.decrypt) {
+            Ok(output) => rows.push(Zeroizing::new(csv_row(&[
+                &entry.value,
+                &uuid.display_uuid(),
+                &output,
+            ]))),
+            Err(e) => {
+                failed += 1;
+                eprintln!(
+                    "{}",
+                    t!("batch_line_error", line = entry.line, error = e)
+                );
+            }
+        }
+    }
+
+    // 预先分配足够的容量，避免扩容时在释放的内存中留下副本
+    let mut csv = Zeroizing::new(String::with_capacity(
+        rows.iter().map(|row| row.len() + 1).sum(),
+    ));
+    for row in &rows {
+        csv.push_str(row);
+        csv.push('\n');
+    }
+    if let Some(passphrase) = &passphrase {
+        csv = match sealed::seal(SEALED_FORMAT, csv.as_bytes(), passphrase) {
+            Ok(content) => Zeroizing::new(content + "\n"),
+            Err(e) => {
+                display_error(&e.to_string());
+                return ExitCode::FAILURE;
+            }
+        };
+    }
+    match &args.output {
+        Some(path) => {
+            if let Err(e) = write_private_file(path, csv.as_bytes()) {
+                display_error(&format!("{}: {}: {}", t!("io_error"), path.display(), e));
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{}", *csv),
+    }
+
+    eprintln!(
+        "{}",
+        t!(
+            "batch_summary",
+            ok = entries.len() - failed,
+            failed = failed
+        )
+    );
+    exit_code(failed == 0)
+}
+
+/// 用口令解密 batch --seal 或 export 创建的文件
+pub fn run_unseal(args: &UnsealArgs) -> ExitCode {
+    if let Some(path) = args.output.as_ref().filter(|path| path.exists()) {
+        display_error(&t!("output_exists", path = path.display()));
+        return ExitCode::FAILURE;
+    }
+    let content = match fs::read_to_string(&args.file) {
+        Ok(content) => content,
+        Err(e) => {
+            display_error(&format!("{}: {}: {}", t!("io_error"), args.file.display(), e));
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(passphrase) = resolve_passphrase(args.passphrase.as_ref(), false) else {
+        return ExitCode::FAILURE;
+    };
+    let plaintext = match sealed::open(&content, &passphrase) {
+        Ok((_, plaintext)) => plaintext,
+        Err(e) => {
+            display_error(&e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(path) = &args.output else {
+        return match io::stdout().write_all(&plaintext) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                display_error(&format!("{}: {}", t!("io_error"), e));
+                ExitCode::FAILURE
+            }
+        };
+    };
+    if let Err(e) = write_private_file(path, &plaintext) {
+        display_error(&format!("{}: {}: {}", t!("io_error"), path.display(), e));
+        return ExitCode::FAILURE;
+    }
+    println!("{}", t!("unseal_written", path = path.display()));
+    ExitCode::SUCCESS
+}
+
+/// 生成在目标机器上写入 ID 的部署脚本
+pub fn run_script(args: &ScriptArgs) -> ExitCode {
+    let encrypted_id = match perform_encrypt(&args.id, &args.uuid) {
+        EncryptResult::Success { encrypted_id, .. } => encrypted_id,
+        EncryptResult::Error(error_msg) => {
+            display_error(&error_msg);
+            return ExitCode::FAILURE;
+        }
+    };
+    let script = render_script(args.format, &args.id, &encrypted_id);
+
+    let Some(path) = &args.output else {
+        print!("{}", script);
+        return ExitCode::SUCCESS;
+    };
+    if path.exists() {
+        display_error(&t!("output_exists", path = path.display()));
+        return ExitCode::FAILURE;
+    }
+    if let Err(e) = write_script(path, &script, args.format) {
+        display_error(&format!("{}: {}: {}", t!("io_error"), path.display(), e));
+        return ExitCode::FAILURE;
+    }
+    println!("{}", t!("script_written", path = path.display()));
+    ExitCode::SUCCESS
+}
+
+/// 写入脚本文件，脚本包含加密 ID，只允许当前用户读写，shell 脚本设置为可执行
+fn write_script(path: &Path, script: &str, format: ScriptFormat) -> io::Result<()> {
+    write_private_file(path, script.as_bytes())?;
+    #[cfg(unix)]
+    if format == ScriptFormat::Sh {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o700))?;
+    }
+    #[cfg(not(unix))]
+    let _ = format;
+    Ok(())
+}
+
+/// 启动本地 HTTP API，直到进程被终止
+pub fn run_serve(args: &ServeArgs) -> ExitCode {
+    let token = match &args.token {
+        Some(token) if token.expose().trim().is_empty() => {
+            display_error(&t!("serve_empty_token"));
+            return ExitCode::FAILURE;
+        }
+        Some(token) => token.expose().trim().to_string(),
+        None => generate_token(),
+    };
+    let id_server = if args.id_server.is_some() || args.config.is_some() {
+        let Some(settings) = resolve_server_settings(
+            args.id_server.as_deref(),
+            args.key.as_deref(),
+            args.config.as_ref(),
+        ) else {
+            return ExitCode::FAILURE;
+        };
+        Some(IdServer {
+            address: settings.rendezvous_server.unwrap_or_default(),
+            key: settings.key.unwrap_or_default(),
+        })
+    } else {
+        detect_id_server(args.key.as_deref())
+    };
+
+    let server = match tiny_http::Server::http(args.bind) {
+        Ok(server) => server,
+        Err(e) => {
+            display_error(&format!("{}: {}: {}", t!("io_error"), args.bind, e));
+            return ExitCode::FAILURE;
+        }
+    };
+    // 绑定端口 0 时由系统分配端口，显示实际监听的地址
+    let address = server.server_addr().to_ip().unwrap_or(args.bind);
+    if !address.ip().is_loopback() {
+        eprintln!("{}", t!("serve_public_warning", address = address));
+    }
+    println!(
+        "{}",
+        t!("serve_listening", url = format!("http://{}", address))
+    );
+    if args.token.is_none() {
+        println!("{}", t!("serve_token_generated", token = token));
+    }
+    match &id_server {
+        Some(id_server) => println!("{}", t!("serve_id_server", server = id_server.address)),
+        None => println!("{}", t!("serve_no_id_server")),
+    }
+
+    serve(&server, &token, id_server.as_ref());
+    ExitCode::SUCCESS
+}
+
+/// 未通过参数指定 ID 服务器时，使用自动检测到的 RustDesk2.toml 中的服务器，找不到时返回 `None`
+fn detect_id_server(key: Option<&str>) -> Option<IdServer> {
+    let path = detect_files(OPTIONS_FILE_NAME).into_iter().next()?;
+    let settings = ServerSettings::from_config(&ConfigFile::load(&path).ok()?);
+    let address = settings.get(ServerOption::RendezvousServer)?;
+    ServerOption::RendezvousServer.validate(address).ok()?;
+    Some(IdServer {
+        address: address.to_string(),
+        key: key.map(str::to_string).or(settings.key).unwrap_or_default(),
+    })
+}
+
+/// 显示检测到的机器 UUID 及其来源
+pub fn run_uuid(args: &UuidArgs) -> ExitCode {
+    let mut success = true;
+    if args.uuids.is_empty() {
+        match machine_uid::get().map(SecretString::new) {
+            Ok(machine_uuid) => {
+                println!(
+                    "{}: {}",
+                    t!("detected_uuid_label"),
+                    machine_uuid.display_uuid()
+                );
+                println!("{}", t!("uuid_source_label", source = uuid_source()));
+                display_uuid_key(&machine_uuid);
+            }
+            Err(e) => {
+                println!("{}: {}", t!("auto_uuid_failed"), e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    for uuid in &args.uuids {
+        println!("{}", t!("uuid_given_label", uuid = uuid.display_uuid()));
+        match validate_uuid(uuid.expose()) {
+            Ok(()) => display_uuid_key(uuid),
+            Err(e) => {
+                display_error(&e.to_string());
+                success = false;
+            }
+        }
+    }
+
+    // 截断后相同的 UUID 可以互相解密对方加密的值
+    for (index, first) in args.uuids.iter().enumerate() {
+        for second in &args.uuids[index + 1..] {
+            if first != second && same_key(first.expose(), second.expose()) {
+                println!(
+                    "{}",
+                    t!(
+                        "uuid_key_collision",
+                        first = first.display_uuid(),
+                        second = second.display_uuid()
+                    )
+                );
+            }
+        }
+    }
+    println!("{}", t!("uuid_form_hint", max = KEY_BYTES));
+    if !is_uuid_revealed() {
+        println!("{}", t!("uuid_masked_hint"));
+    }
+    exit_code(success)
+}
+
+/// 显示 UUID 实际作为密钥的部分，以及另一种写法的 UUID
+fn display_uuid_key(uuid: &SecretString) {
+    let key = UuidKey::new(uuid.expose());
+    println!(
+        "{}",
+        t!(
+            "uuid_key_effective",
+            key = display_key(uuid),
+            used = key.used,
+            length = key.length
+        )
+    );
+    if key.truncated() > 0 {
+        println!("{}", t!("uuid_key_truncated", count = key.truncated()));
+    }
+    if key.padding() > 0 {
+        println!("{}", t!("uuid_key_padded", count = key.padding()));
+    }
+    let Some(form) = UuidForm::detect(uuid.expose()) else {
+        return;
+    };
+    if let Ok(other) = convert_uuid(uuid, form.other()) {
+        println!(
+            "{}",
+            t!(
+                "uuid_key_other_form",
+                form = form.other().name(),
+                uuid = other.display_uuid()
+            )
+        );
+    }
+}
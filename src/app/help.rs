@@ -1,78 +1,110 @@
+use crate::app::cli::Cli;
+use clap::{Command, CommandFactory};
 use rust_i18n::t;
+use std::sync::OnceLock;
+
+/// 本地化后的命令定义，按启动时确定的语言构建一次
+static LOCALIZED_COMMAND: OnceLock<Command<'static>> = OnceLock::new();
+
+/// 构建带有本地化帮助文本的命令定义
+///
+/// 参数和子命令来自 `Cli` 的定义。命令说明使用 `<命令路径>_about`，参数说明依次查找
+/// `<命令路径>_arg_<参数>` 和 `arg_<参数>`，缺失翻译时保留定义中的英文文本。
+pub fn localized_command() -> Command<'static> {
+    LOCALIZED_COMMAND
+        .get_or_init(|| {
+            localize_command(Cli::command(), "cli").after_long_help(leak(long_help_footer()))
+        })
+        .clone()
+}
 
 /// 显示详细的帮助信息
-pub fn show_help(_lang: &str) {
+pub fn show_help() {
     println!();
     println!("{}", t!("help_title"));
     println!();
-
-    show_program_function();
-    show_cli_usage();
-    show_cli_parameters();
-    show_cli_examples();
-    show_config_file_location();
-    show_notes();
-    show_get_help();
-}
-
-/// 显示程序功能说明
-fn show_program_function() {
-    println!("{}", t!("program_function_title"));
-    println!("{}", t!("program_function_desc1"));
-    println!("{}", t!("program_function_desc2"));
+    let _ = localized_command().print_long_help();
     println!();
 }
 
-/// 显示命令行用法
-fn show_cli_usage() {
-    println!("{}", t!("cli_usage_title"));
-    println!("{}", t!("cli_usage_encrypt"));
-    println!("{}", t!("cli_usage_decrypt"));
-    println!();
-}
+/// 递归地为命令及其子命令设置本地化文本
+fn localize_command(mut command: Command<'static>, path: &str) -> Command<'static> {
+    if let Some(about) = lookup(&format!("{}_about", path)) {
+        command = command.about(leak(about));
+    }
 
-/// 显示命令行参数
-fn show_cli_parameters() {
-    println!("{}", t!("cli_params_title"));
-    println!("{}", t!("cli_param_id"));
-    println!("{}", t!("cli_param_eid"));
-    println!("{}", t!("cli_param_uuid"));
-    println!("{}", t!("cli_param_lang"));
-    println!("{}", t!("cli_param_help"));
-    println!();
-}
+    // 修改自动生成的 version 参数会使它被当作普通的全局参数传递给没有版本号的子命令
+    let arg_ids: Vec<&'static str> = command
+        .get_arguments()
+        .map(|arg| arg.get_id())
+        .filter(|id| *id != "version")
+        .collect();
+    for id in arg_ids {
+        let key = id.replace('-', "_");
+        let help = lookup(&format!("{}_arg_{}", path, key))
+            .or_else(|| lookup(&format!("arg_{}", key)));
+        if let Some(help) = help {
+            command = command.mut_arg(id, |arg| arg.help(leak(help)));
+        }
+    }
 
-/// 显示命令行示例
-fn show_cli_examples() {
-    println!("{}", t!("cli_examples_title"));
-    println!("{}", t!("cli_example_encrypt"));
-    println!("{}", t!("cli_example_decrypt"));
-    println!("{}", t!("cli_example_help"));
-    println!();
+    let names: Vec<String> = command
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect();
+    for name in names {
+        let sub_path = format!("{}_{}", path, name.replace('-', "_"));
+        command = command.mut_subcommand(name.as_str(), |sub| localize_command(sub, &sub_path));
+    }
+
+    command
 }
 
-/// 显示配置文件位置
-fn show_config_file_location() {
-    println!("{}", t!("config_file_location_title"));
-    println!("{}", t!("config_file_location_macos"));
-    println!("{}", t!("config_file_location_windows"));
-    println!("{}", t!("config_file_location_windows_service"));
-    println!();
+/// 查找翻译，缺失时返回 `None`
+fn lookup(key: &str) -> Option<String> {
+    let text = t!(key);
+    let missing = format!("{}.{}", rust_i18n::locale(), key);
+    (text != missing).then_some(text)
 }
 
-/// 显示注意事项
-fn show_notes() {
-    println!("{}", t!("notes_title"));
-    println!("{}", t!("note_1"));
-    println!("{}", t!("note_2"));
-    println!("{}", t!("note_3"));
-    println!();
+/// clap 3 的帮助文本需要 `'static` 生命周期，命令定义只构建一次，泄漏的文本不会重复分配
+fn leak(text: String) -> &'static str {
+    Box::leak(text.into_boxed_str())
 }
 
-/// 显示获取帮助信息
-fn show_get_help() {
-    println!("{}", t!("get_help_title"));
-    println!("{}", t!("get_help_github"));
-    println!("{}", t!("get_help_email"));
-    println!();
+/// 详细帮助末尾的补充说明：功能、示例、配置文件位置、注意事项和联系方式
+fn long_help_footer() -> String {
+    let sections: [&[&str]; 5] = [
+        &[
+            "program_function_title",
+            "program_function_desc1",
+            "program_function_desc2",
+        ],
+        &[
+            "cli_examples_title",
+            "cli_example_encrypt",
+            "cli_example_decrypt",
+            "cli_example_apply",
+            "cli_example_completions",
+        ],
+        &[
+            "config_file_location_title",
+            "config_file_location_macos",
+            "config_file_location_windows",
+            "config_file_location_windows_service",
+        ],
+        &["notes_title", "note_1", "note_2", "note_3"],
+        &["get_help_title", "get_help_github", "get_help_email"],
+    ];
+
+    sections
+        .iter()
+        .map(|keys| {
+            keys.iter()
+                .map(|key| t!(key))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
 }
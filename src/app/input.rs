@@ -1,17 +1,178 @@
-use std::io::{self, Write};
+//! 用户输入模块
+//!
+//! 标准输入是终端时使用行编辑器读取输入，支持光标移动编辑、会话内的历史记录和菜单选项的 Tab 补全；
+//! 否则逐行读取标准输入。输入结束 (Ctrl-D) 和中断 (Ctrl-C) 都视为取消。
+//! 密码、UUID 和口令等敏感输入在终端中以 `*` 遮盖，不去除首尾空白，读取后在释放时清零。
 
-/// 读取用户输入的一行文本
-pub fn read_line() -> String {
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
-    input.trim().to_string()
+use crate::core::secret::SecretString;
+use rustyline::completion::Completer;
+use rustyline::config::Configurer;
+use rustyline::highlight::{CmdKind, Highlighter};
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{ColorMode, CompletionType, Config, Context, Editor, Helper};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io::{self, IsTerminal, Write};
+use unicode_width::UnicodeWidthStr;
+use zeroize::Zeroizing;
+
+/// 补全当前提示可选的值，读取敏感输入时遮盖显示的内容
+#[derive(Default)]
+pub struct ChoiceHelper {
+    choices: Vec<String>,
+    mask: bool,
+}
+
+impl ChoiceHelper {
+    /// 使用给定的候选值创建
+    pub fn new(choices: &[&str]) -> Self {
+        ChoiceHelper {
+            choices: choices.iter().map(|choice| choice.to_string()).collect(),
+            mask: false,
+        }
+    }
+
+    /// 遮盖输入内容，用于读取敏感输入
+    pub fn masked() -> Self {
+        ChoiceHelper {
+            choices: Vec::new(),
+            mask: true,
+        }
+    }
+}
+
+impl Completer for ChoiceHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let prefix = line[..pos].trim_start();
+        let candidates = self
+            .choices
+            .iter()
+            .filter(|choice| choice.starts_with(prefix))
+            .cloned()
+            .collect();
+        Ok((pos - prefix.len(), candidates))
+    }
 }
 
-/// 显示提示并读取用户输入
-pub fn prompt_input(prompt: &str) -> String {
+impl Hinter for ChoiceHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ChoiceHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        if self.mask {
+            Cow::Owned("*".repeat(line.width()))
+        } else {
+            Cow::Borrowed(line)
+        }
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, kind: CmdKind) -> bool {
+        self.mask && kind != CmdKind::MoveCursor
+    }
+}
+
+impl Validator for ChoiceHelper {}
+
+impl Helper for ChoiceHelper {}
+
+type LineEditor = Editor<ChoiceHelper, DefaultHistory>;
+
+thread_local! {
+    /// 整个会话共用的行编辑器，保存历史记录；标准输入不是终端时为 `None`
+    static EDITOR: RefCell<Option<LineEditor>> = RefCell::new(create_editor());
+}
+
+/// 创建行编辑器
+fn create_editor() -> Option<LineEditor> {
+    if !io::stdin().is_terminal() {
+        return None;
+    }
+    let config = Config::builder()
+        .completion_type(CompletionType::List)
+        .auto_add_history(false)
+        .build();
+    let mut editor = LineEditor::with_config(config).ok()?;
+    editor.set_helper(Some(ChoiceHelper::default()));
+    Some(editor)
+}
+
+/// 读取一行输入
+///
+/// `choices` 为 Tab 补全的候选值，`history` 表示是否将输入加入历史记录。
+/// 输入结束、被中断或读取失败时返回 `None`。
+fn read_input(prompt: &str, choices: &[&str], history: bool) -> Option<String> {
+    EDITOR.with(|editor| match editor.borrow_mut().as_mut() {
+        Some(editor) => {
+            if let Some(helper) = editor.helper_mut() {
+                *helper = ChoiceHelper::new(choices);
+            }
+            let line = editor.readline(prompt).ok()?;
+            let line = line.trim().to_string();
+            if history && !line.is_empty() {
+                let _ = editor.add_history_entry(line.as_str());
+            }
+            Some(line)
+        }
+        None => read_plain_line(prompt).map(|line| line.trim().to_string()),
+    })
+}
+
+/// 读取一行敏感输入，终端中遮盖显示，只去除行尾的换行符
+fn read_secret(prompt: &str) -> Option<SecretString> {
+    EDITOR.with(|editor| match editor.borrow_mut().as_mut() {
+        Some(editor) => {
+            if let Some(helper) = editor.helper_mut() {
+                *helper = ChoiceHelper::masked();
+            }
+            // 强制启用高亮，确保遮盖在任何终端中生效
+            editor.set_color_mode(ColorMode::Forced);
+            let line = editor.readline(prompt);
+            editor.set_color_mode(ColorMode::Enabled);
+            line.ok().map(SecretString::new)
+        }
+        None => {
+            let mut line = Zeroizing::new(read_plain_line(prompt)?);
+            let len = line.trim_end_matches(['\r', '\n']).len();
+            line.truncate(len);
+            Some(SecretString::new(std::mem::take(&mut *line)))
+        }
+    })
+}
+
+/// 标准输入不是终端时，显示提示并读取一行（包括行尾的换行符）
+fn read_plain_line(prompt: &str) -> Option<String> {
     print!("{}", prompt);
-    io::stdout().flush().unwrap();
-    read_line()
+    io::stdout().flush().ok()?;
+    let mut input = String::new();
+    match io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(input),
+    }
+}
+
+/// 显示提示并读取用户输入，取消时返回 `None`
+pub fn prompt_input(prompt: &str) -> Option<String> {
+    read_input(prompt, &[], true)
+}
+
+/// 读取密码、UUID 和口令等敏感输入，遮盖显示且不加入历史记录，取消时返回 `None`
+pub fn prompt_secret(prompt: &str) -> Option<SecretString> {
+    read_secret(prompt)
+}
+
+/// 读取菜单或确认选项，支持 Tab 补全，不加入历史记录，取消时返回 `None`
+pub fn prompt_choice(prompt: &str, choices: &[&str]) -> Option<String> {
+    read_input(prompt, choices, false)
 }
 
 /// 验证输入是否为空
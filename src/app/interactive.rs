@@ -1,17 +1,62 @@
+use crate::app::commands::{
+    apply_updates, decrypt_password, encrypt_password, encrypt_updates, inspect_configs,
+    resolve_config_paths,
+};
 use crate::app::help::show_help;
-use crate::app::input::{is_empty_input, prompt_input};
+use crate::app::input::{is_empty_input, prompt_choice, prompt_input, prompt_secret};
 use crate::app::uuid::get_uuid_input;
 use crate::core::handler::{
     display_decrypt_success, display_encrypt_success, display_error, perform_decrypt,
     perform_encrypt, DecryptResult, EncryptResult,
 };
+use crate::core::secret::SecretString;
 use rust_i18n::t;
+use std::path::PathBuf;
+
+/// 菜单中可选的编号
+const MENU_CHOICES: [&str; 9] = ["0", "1", "2", "3", "4", "5", "6", "7", "8"];
+
+/// 交互会话状态，在多次操作之间保留已确认的 UUID
+#[derive(Default)]
+struct Session {
+    uuid: Option<SecretString>,
+}
+
+impl Session {
+    /// 获取本次会话使用的 UUID，首次使用时请求确认，取消时返回 `None`
+    fn uuid(&mut self) -> Option<SecretString> {
+        if let Some(uuid) = &self.uuid {
+            println!("{}", t!("session_uuid_reused", uuid = uuid.display_uuid()));
+            println!();
+            return Some(uuid.clone());
+        }
+
+        let uuid = get_uuid_input();
+        if uuid.is_empty() {
+            return None;
+        }
+        self.uuid = Some(uuid.clone());
+        Some(uuid)
+    }
+
+    /// 忘记已确认的 UUID 并重新选择
+    fn change_uuid(&mut self) {
+        println!();
+        println!("{}", t!("change_uuid_title"));
+        println!();
+        self.uuid = None;
+        if self.uuid().is_some() {
+            println!("{}", t!("session_uuid_updated"));
+        }
+    }
+}
 
 /// 显示交互式主菜单
-pub fn show_interactive_menu(lang: &str) {
+pub fn show_interactive_menu() {
     show_logo();
+    show_banner();
     show_menu_options();
-    handle_menu_loop(lang);
+    handle_menu_loop();
 }
 
 /// 显示 ASCII Logo
@@ -31,48 +76,58 @@ fn show_logo() {
     );
 }
 
-/// 显示菜单选项
-fn show_menu_options() {
+/// 显示欢迎信息
+fn show_banner() {
     println!("═══════════════════════════════════════════════════════════");
     println!("{}", t!("welcome"));
     println!("{}", t!("description"));
     println!("{}", t!("security"));
     println!("═══════════════════════════════════════════════════════════");
     println!();
+}
 
+/// 显示菜单选项
+fn show_menu_options() {
     println!("{}", t!("choose_action"));
     println!();
-    println!("{}", t!("generate_id"));
-    println!("{}", t!("generate_id_desc"));
-    println!();
-    println!("{}", t!("validate_id"));
-    println!("{}", t!("validate_id_desc"));
-    println!();
-    println!("{}", t!("help"));
-    println!("{}", t!("help_desc"));
-    println!();
+    for (title, desc) in [
+        ("generate_id", "generate_id_desc"),
+        ("validate_id", "validate_id_desc"),
+        ("apply_config", "apply_config_desc"),
+        ("inspect_config", "inspect_config_desc"),
+        ("encrypt_password", "encrypt_password_desc"),
+        ("decrypt_password", "decrypt_password_desc"),
+        ("help", "help_desc"),
+        ("change_uuid", "change_uuid_desc"),
+    ] {
+        println!("{}", t!(title));
+        println!("{}", t!(desc));
+        println!();
+    }
     println!("{}", t!("exit"));
     println!();
 }
 
-/// 处理菜单循环
-fn handle_menu_loop(lang: &str) {
+/// 处理菜单循环，每次操作完成后返回菜单，直到选择退出或输入结束
+fn handle_menu_loop() {
+    let mut session = Session::default();
+
     loop {
-        let choice = prompt_input(&t!("enter_choice"));
+        let Some(choice) = prompt_choice(&t!("enter_choice"), &MENU_CHOICES) else {
+            println!();
+            println!("{}", t!("thanks"));
+            break;
+        };
 
         match choice.as_str() {
-            "1" => {
-                handle_encrypt_mode(lang);
-                break;
-            }
-            "2" => {
-                handle_decrypt_mode(lang);
-                break;
-            }
-            "3" => {
-                show_help(lang);
-                break;
-            }
+            "1" => handle_encrypt_mode(&mut session),
+            "2" => handle_decrypt_mode(&mut session),
+            "3" => handle_apply_mode(&mut session),
+            "4" => handle_inspect_mode(&mut session),
+            "5" => handle_password_encrypt_mode(&mut session),
+            "6" => handle_password_decrypt_mode(&mut session),
+            "7" => show_help(),
+            "8" => session.change_uuid(),
             "0" => {
                 println!("{}", t!("thanks"));
                 break;
@@ -80,19 +135,25 @@ fn handle_menu_loop(lang: &str) {
             _ => {
                 println!("{}", t!("invalid_choice"));
                 println!();
+                continue;
             }
         }
+
+        println!();
+        show_menu_options();
     }
 }
 
 /// 处理加密模式
-fn handle_encrypt_mode(_lang: &str) {
+fn handle_encrypt_mode(session: &mut Session) {
     println!();
     println!("{}", t!("encrypt_mode_title"));
     println!();
 
     // 获取自定义 ID
-    let custom_id = prompt_input(&t!("enter_custom_id"));
+    let Some(custom_id) = report_cancel(prompt_input(&t!("enter_custom_id"))) else {
+        return;
+    };
 
     if is_empty_input(&custom_id) {
         println!("{}", t!("empty_id_error"));
@@ -100,10 +161,9 @@ fn handle_encrypt_mode(_lang: &str) {
     }
 
     // 获取 UUID
-    let uuid = get_uuid_input();
-    if uuid.is_empty() {
+    let Some(uuid) = session.uuid() else {
         return;
-    }
+    };
 
     // 执行加密
     let result = perform_encrypt(&custom_id, &uuid);
@@ -118,13 +178,15 @@ fn handle_encrypt_mode(_lang: &str) {
 }
 
 /// 处理解密模式
-fn handle_decrypt_mode(_lang: &str) {
+fn handle_decrypt_mode(session: &mut Session) {
     println!();
     println!("{}", t!("decrypt_mode_title"));
     println!();
 
     // 获取加密 ID
-    let enc_id = prompt_input(&t!("enter_encrypted_id"));
+    let Some(enc_id) = report_cancel(prompt_input(&t!("enter_encrypted_id"))) else {
+        return;
+    };
 
     if is_empty_input(&enc_id) {
         println!("{}", t!("empty_encrypted_id_error"));
@@ -137,10 +199,9 @@ fn handle_decrypt_mode(_lang: &str) {
     }
 
     // 获取 UUID
-    let uuid = get_uuid_input();
-    if uuid.is_empty() {
+    let Some(uuid) = session.uuid() else {
         return;
-    }
+    };
 
     // 执行解密
     let result = perform_decrypt(&enc_id, &uuid);
@@ -153,3 +214,112 @@ fn handle_decrypt_mode(_lang: &str) {
         }
     }
 }
+
+/// 处理写入配置文件
+fn handle_apply_mode(session: &mut Session) {
+    println!();
+    println!("{}", t!("apply_mode_title"));
+    println!();
+
+    let Some(custom_id) = report_cancel(prompt_input(&t!("enter_apply_custom_id"))) else {
+        return;
+    };
+    let Some(password) = report_cancel(prompt_secret(&t!("enter_apply_password"))) else {
+        return;
+    };
+
+    if is_empty_input(&custom_id) && password.is_empty() {
+        println!("{}", t!("apply_nothing_error"));
+        return;
+    }
+
+    let Some(paths) = prompt_config_paths() else {
+        return;
+    };
+    let Some(uuid) = session.uuid() else {
+        return;
+    };
+
+    let custom_id = (!is_empty_input(&custom_id)).then_some(custom_id.as_str());
+    let password = (!password.is_empty()).then_some(&password);
+    if let Some(updates) = encrypt_updates(custom_id, password, &uuid) {
+        apply_updates(&paths, &updates, false, true);
+    }
+}
+
+/// 处理查看配置文件
+fn handle_inspect_mode(session: &mut Session) {
+    println!();
+    println!("{}", t!("inspect_mode_title"));
+    println!();
+
+    let Some(paths) = prompt_config_paths() else {
+        return;
+    };
+    let Some(uuid) = session.uuid() else {
+        return;
+    };
+
+    inspect_configs(&paths, &uuid);
+}
+
+/// 处理永久密码加密
+fn handle_password_encrypt_mode(session: &mut Session) {
+    println!();
+    println!("{}", t!("password_encrypt_mode_title"));
+    println!();
+
+    let Some(password) = report_cancel(prompt_secret(&t!("enter_password"))) else {
+        return;
+    };
+    if password.is_empty() {
+        println!("{}", t!("empty_password_error"));
+        return;
+    }
+
+    let Some(uuid) = session.uuid() else {
+        return;
+    };
+
+    encrypt_password(&password, &uuid);
+}
+
+/// 处理永久密码解密
+fn handle_password_decrypt_mode(session: &mut Session) {
+    println!();
+    println!("{}", t!("password_decrypt_mode_title"));
+    println!();
+
+    let Some(enc_password) = report_cancel(prompt_input(&t!("enter_encrypted_password"))) else {
+        return;
+    };
+    if is_empty_input(&enc_password) {
+        println!("{}", t!("empty_encrypted_password_error"));
+        return;
+    }
+
+    let Some(uuid) = session.uuid() else {
+        return;
+    };
+
+    decrypt_password(&enc_password, &uuid);
+}
+
+/// 输入被取消时显示提示，返回原输入
+fn report_cancel<T>(input: Option<T>) -> Option<T> {
+    if input.is_none() {
+        println!();
+        println!("{}", t!("operation_cancelled"));
+    }
+    input
+}
+
+/// 询问配置文件路径，留空时自动检测，取消时返回 `None`
+fn prompt_config_paths() -> Option<Vec<PathBuf>> {
+    let path = report_cancel(prompt_input(&t!("enter_config_path")))?;
+    if is_empty_input(&path) {
+        resolve_config_paths(&[])
+    } else {
+        resolve_config_paths(&[PathBuf::from(path)])
+    }
+}
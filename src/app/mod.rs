@@ -1,6 +1,8 @@
 pub mod cli;
+pub mod commands;
 pub mod help;
 pub mod input;
 pub mod interactive;
 pub mod tests;
+pub mod tui;
 pub mod uuid;
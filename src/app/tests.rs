@@ -3,10 +3,58 @@
 //! 包含各个模块的单元测试。
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
 
+    use crate::app::cli::UuidArg;
+    use crate::app::input::ChoiceHelper;
+    use crate::app::tui::{draw, initial_uuid, Focus, Status, TuiApp};
+    use crate::core::api::{handle_request, IdServer};
+    use crate::core::auto_id::{auto_id, parse_mac_address};
+    use crate::core::batch::{csv_row, parse_batch, SEALED_FORMAT};
+    use crate::core::bundle::{open_identity, seal_identity, Identity};
+    use crate::core::clipboard::{
+        copy_to_clipboard, create_backend, osc52_sequence, set_backend, set_clear_after,
+        wait_for_pending_clear, ClipboardBackend, ClipboardMode, FileClipboard, MemoryClipboard,
+        NoopClipboard,
+    };
+    use crate::core::crypto;
+    use crate::core::config::{diff_lines, ConfigFile, ConfigUpdate, DiffLine};
     use crate::core::error::AppError;
-    use crate::core::validation::{validate_custom_id, validate_encrypted_id, validate_uuid};
+    use crate::core::handler::{
+        decrypt_value, encrypt_value, perform_decrypt, perform_encrypt, DecryptResult,
+        EncryptResult,
+    };
+    use crate::core::locale::normalize_locale;
+    use crate::core::options::{ServerOption, ServerSettings};
+    use crate::core::rekey::{plan_rekey, RekeyStatus};
+    use crate::core::rendezvous::{
+        check_id, encode_frame, read_frame, register_pk, rendezvous_message, with_default_port,
+        IdStatus, OnlineResponse, PunchHoleFailure, PunchHoleResponse, RegisterPkResponse,
+        RegisterPkResult, RendezvousMessage,
+    };
+    use crate::core::sealed;
+    use crate::core::secret::{mask_uuid, SecretString};
+    use crate::core::uuid_key::{convert_uuid, same_key, UuidForm, UuidKey};
+    use crate::core::validation::{
+        custom_id_warnings, validate_api_server, validate_custom_id, validate_encrypted_id,
+        validate_password, validate_public_key, validate_server_address, validate_uuid,
+    };
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::Terminal;
+    use rustyline::completion::Completer;
+    use rustyline::highlight::{CmdKind, Highlighter};
+    use rustyline::history::DefaultHistory;
+    use rustyline::Context;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// 剪切板后端是全局状态，相关测试需要串行执行
+    static CLIPBOARD_LOCK: Mutex<()> = Mutex::new(());
 
     /// 测试 UUID 验证
     #[test]
@@ -38,6 +86,22 @@ mod tests {
         assert!(validate_custom_id(&"a".repeat(101)).is_err());
     }
 
+    /// 测试 ID 是否符合 RustDesk 要求的警告
+    #[test]
+    fn test_custom_id_warnings() {
+        assert!(custom_id_warnings("TestComputer").is_empty());
+        assert!(custom_id_warnings("office_pc-01").is_empty());
+
+        // 太短且以数字开头
+        assert_eq!(custom_id_warnings("1ab").len(), 2);
+        // 含有不允许的字符
+        assert_eq!(custom_id_warnings("user@example").len(), 1);
+        // 首尾有空白
+        assert_eq!(custom_id_warnings(" office01").len(), 1);
+        // 无效的 ID 只报告验证错误
+        assert_eq!(custom_id_warnings("").len(), 1);
+    }
+
     /// 测试加密 ID 验证
     #[test]
     fn test_encrypted_id_validation() {
@@ -70,4 +134,1091 @@ mod tests {
         let display = format!("{}", error);
         assert!(display.contains("测试错误"));
     }
+
+    /// 测试 OSC 52 转义序列
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("abc", false), "\x1b]52;c;YWJj\x07");
+        assert_eq!(
+            osc52_sequence("abc", true),
+            "\x1bPtmux;\x1b\x1b]52;c;YWJj\x07\x1b\\"
+        );
+    }
+
+    /// 测试剪切板后端
+    #[test]
+    fn test_clipboard_backends() {
+        let _lock = CLIPBOARD_LOCK.lock().unwrap();
+        let memory = MemoryClipboard::default();
+        set_backend(Box::new(memory.clone()));
+        assert_eq!(copy_to_clipboard("test123"), Ok(true));
+        assert_eq!(memory.contents.lock().unwrap().as_deref(), Some("test123"));
+
+        set_backend(Box::new(NoopClipboard));
+        assert_eq!(copy_to_clipboard("test123"), Ok(false));
+
+        let path = std::env::temp_dir().join(format!("clipboard-test-{}", std::process::id()));
+        let mut file = FileClipboard { path: path.clone() };
+        assert!(file.set_text("test123").is_ok());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "test123");
+        let _ = std::fs::remove_file(&path);
+
+        assert!(create_backend(ClipboardMode::File, Some(path)).is_ok());
+        assert!(create_backend(ClipboardMode::None, None).is_ok());
+    }
+
+    /// 测试剪切板自动清除
+    #[test]
+    fn test_clipboard_clear_after() {
+        let _lock = CLIPBOARD_LOCK.lock().unwrap();
+        let memory = MemoryClipboard::default();
+        set_backend(Box::new(memory.clone()));
+        set_clear_after(Some(Duration::from_millis(10)));
+
+        // 恢复第一次复制之前的内容，而不是上一次复制的值
+        *memory.contents.lock().unwrap() = Some("previous".to_string());
+        assert_eq!(copy_to_clipboard("secret1"), Ok(true));
+        assert_eq!(copy_to_clipboard("secret2"), Ok(true));
+        wait_for_pending_clear();
+        assert_eq!(memory.contents.lock().unwrap().as_deref(), Some("previous"));
+
+        // 剪切板已被其他程序修改时不做处理
+        assert_eq!(copy_to_clipboard("secret"), Ok(true));
+        *memory.contents.lock().unwrap() = Some("changed".to_string());
+        wait_for_pending_clear();
+        assert_eq!(memory.contents.lock().unwrap().as_deref(), Some("changed"));
+
+        // 之前没有内容时清空剪切板
+        *memory.contents.lock().unwrap() = None;
+        assert_eq!(copy_to_clipboard("secret"), Ok(true));
+        wait_for_pending_clear();
+        assert_eq!(memory.contents.lock().unwrap().as_deref(), None);
+
+        set_clear_after(None);
+    }
+
+    /// 测试敏感数据不会出现在 Debug 输出中
+    #[test]
+    fn test_secret_redaction() {
+        let uuid = SecretString::from("550e8400-e29b-41d4-a716-446655440000");
+        assert!(!format!("{:?}", uuid).contains("550e8400"));
+
+        let encrypted = match perform_encrypt("secret-id", &uuid) {
+            EncryptResult::Success { encrypted_id, .. } => format!("00{}", encrypted_id),
+            EncryptResult::Error(e) => panic!("{}", e),
+        };
+        let result = perform_decrypt(&encrypted, &uuid);
+        match &result {
+            DecryptResult::Success { decrypted_id, .. } => {
+                assert_eq!(decrypted_id.expose(), "secret-id")
+            }
+            DecryptResult::Error(e) => panic!("{}", e),
+        }
+        assert!(!format!("{:?}", result).contains("secret-id"));
+    }
+
+    /// 测试 UUID 遮盖
+    #[test]
+    fn test_mask_uuid() {
+        assert_eq!(
+            mask_uuid("3C17252C-4A25-54AB-8A92-B88D3D6665AA"),
+            "3C17****-****-****-****-********65AA"
+        );
+        assert_eq!(
+            mask_uuid("3d1219c7c4c5404aaa1f6d2a48adfda4"),
+            "3d12************************fda4"
+        );
+        assert_eq!(mask_uuid("short-id"), "*****-**");
+    }
+
+    /// 测试永久密码验证
+    #[test]
+    fn test_password_validation() {
+        assert!(validate_password("S3cret!pass").is_ok());
+        assert!(validate_password(&"密".repeat(128)).is_ok());
+
+        assert!(validate_password("").is_err());
+        assert!(validate_password(&"a".repeat(129)).is_err());
+        assert!(validate_password("pass\nword").is_err());
+    }
+
+    /// 测试按行差异
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc\n", "a\nB\nc\nd\n");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Removed("b".to_string()),
+                DiffLine::Added("B".to_string()),
+                DiffLine::Added("d".to_string()),
+            ]
+        );
+        assert!(diff_lines("same\n", "same\n").is_empty());
+    }
+
+    /// 测试修改配置文件时保留其他内容
+    #[test]
+    fn test_config_file_set_str() {
+        let content = "enc_id = '00old'\nsalt = 'abc'\n\n[keys_confirmed]\nserver = true\n";
+        let mut config = ConfigFile::parse(Path::new("RustDesk.toml"), content).unwrap();
+        assert_eq!(config.get_str("enc_id"), Some("00old"));
+
+        config.set_str("enc_id", "00old");
+        assert!(!config.is_modified());
+
+        config.set_str("enc_id", "00new");
+        config.set_str("password", "00pass");
+        assert!(config.is_modified());
+        let updated = config.content();
+        assert!(updated.contains("enc_id = \"00new\""));
+        assert!(updated.contains("password = \"00pass\""));
+        assert!(updated.contains("salt = 'abc'"));
+        assert!(updated.contains("[keys_confirmed]\nserver = true"));
+
+        assert!(ConfigFile::parse(Path::new("RustDesk.toml"), "enc_id = ").is_err());
+    }
+
+    /// 测试用新 UUID 重新加密配置文件中的加密字段
+    #[test]
+    fn test_plan_rekey() {
+        let old_uuid = SecretString::from("12345678-1234-1234-1234-123456789012");
+        let new_uuid = SecretString::from("87654321-4321-4321-4321-210987654321");
+        let password = encrypt_value(&SecretString::from("Secret123"), &old_uuid).unwrap();
+        let content = format!(
+            "enc_id = '00Nhyr/aazYBq3jkSuNwA6trMEV2xPMoziEAJWog=='\npassword = '{}'\nsalt = '00abc'\n",
+            password
+        );
+        let mut config = ConfigFile::parse(Path::new("RustDesk.toml"), &content).unwrap();
+
+        let plan = plan_rekey(&config, &old_uuid, &new_uuid).unwrap();
+        assert_eq!(
+            plan.fields,
+            [("enc_id", RekeyStatus::Rekeyed), ("password", RekeyStatus::Rekeyed)]
+        );
+        for update in &plan.updates {
+            config.apply(update).unwrap();
+        }
+        let enc_id = config.get_str("enc_id").unwrap();
+        assert_eq!(decrypt_value(enc_id, &new_uuid).unwrap().expose(), "TestComputer");
+        let password = config.get_str("password").unwrap();
+        assert_eq!(decrypt_value(password, &new_uuid).unwrap().expose(), "Secret123");
+        assert_eq!(config.get_str("salt"), Some("00abc"));
+
+        // 重复执行时不再修改
+        let plan = plan_rekey(&config, &old_uuid, &new_uuid).unwrap();
+        assert!(plan.updates.is_empty());
+        assert_eq!(plan.fields[0], ("enc_id", RekeyStatus::AlreadyRekeyed));
+
+        // 两个 UUID 都无法解密时整个文件都不修改
+        let other = SecretString::from("11111111-1111-1111-1111-111111111111");
+        let error = plan_rekey(&config, &other, &old_uuid).unwrap_err();
+        assert_eq!(error.kind(), "crypto");
+        assert!(error.message().contains("enc_id"));
+
+        let plain = ConfigFile::parse(Path::new("RustDesk.toml"), "password = 'plain'\n").unwrap();
+        assert!(plan_rekey(&plain, &old_uuid, &new_uuid).unwrap().fields.is_empty());
+    }
+
+    /// 测试导出身份到迁移包并在另一台机器上导入
+    #[test]
+    fn test_identity_bundle() {
+        let old_uuid = SecretString::from("12345678-1234-1234-1234-123456789012");
+        let new_uuid = SecretString::from("87654321-4321-4321-4321-210987654321");
+        let secret_key: Vec<String> = (0..64).map(|byte: u8| byte.to_string()).collect();
+        let public_key: Vec<String> = (64..96).map(|byte: u8| byte.to_string()).collect();
+        let content = format!(
+            "enc_id = '00Nhyr/aazYBq3jkSuNwA6trMEV2xPMoziEAJWog=='\npassword = 'legacy'\nkey_pair = [[{}], [{}]]\n",
+            secret_key.join(", "),
+            public_key.join(", ")
+        );
+        let config = ConfigFile::parse(Path::new("RustDesk.toml"), &content).unwrap();
+        let options = ConfigFile::parse(
+            Path::new("RustDesk2.toml"),
+            "[options]\ncustom-rendezvous-server = 'rd.example.com'\nallow-remote-config-modification = 'Y'\n",
+        )
+        .unwrap();
+
+        let identity = Identity::from_configs(&config, Some(&options), &old_uuid).unwrap();
+        assert_eq!(identity.id.as_deref(), Some("TestComputer"));
+        assert_eq!(identity.password.as_deref(), Some("legacy"));
+        assert!(identity.has_key_pair());
+        assert_eq!(identity.options.len(), 2);
+        assert!(Identity::from_configs(&config, None, &new_uuid).is_err());
+
+        let passphrase = SecretString::from("correct horse");
+        let bundle = seal_identity(&identity, &passphrase).unwrap();
+        assert!(!bundle.contains("TestComputer"));
+        assert!(!bundle.contains("legacy"));
+
+        let error = open_identity(&bundle, &SecretString::from("wrong")).unwrap_err();
+        assert_eq!(error.kind(), "crypto");
+        let tampered = bundle.replace("\"version\": 1", "\"version\": 2");
+        assert_eq!(
+            open_identity(&tampered, &passphrase).unwrap_err().kind(),
+            "validation"
+        );
+        assert!(open_identity("{}", &passphrase).is_err());
+
+        let restored = open_identity(&bundle, &passphrase).unwrap();
+        let mut target =
+            ConfigFile::parse(Path::new("RustDesk.toml"), "enc_id = '00other'\nsalt = 'abc'\n")
+                .unwrap();
+        for update in restored.config_updates(&new_uuid).unwrap() {
+            target.apply(&update).unwrap();
+        }
+        restored.apply_key_pair(&mut target).unwrap();
+        let enc_id = target.get_str("enc_id").unwrap();
+        assert_eq!(decrypt_value(enc_id, &new_uuid).unwrap().expose(), "TestComputer");
+        let password = target.get_str("password").unwrap();
+        assert_eq!(decrypt_value(password, &new_uuid).unwrap().expose(), "legacy");
+        assert_eq!(
+            target.get_nested_bytes("key_pair", 1),
+            Some((64..96).collect::<Vec<u8>>())
+        );
+        assert_eq!(target.get_str("salt"), Some("abc"));
+
+        let mut target_options = ConfigFile::parse(Path::new("RustDesk2.toml"), "").unwrap();
+        for update in restored.options_updates() {
+            target_options.apply(&update).unwrap();
+        }
+        assert_eq!(
+            target_options.get_option("custom-rendezvous-server"),
+            Some("rd.example.com")
+        );
+    }
+
+    /// 测试用口令加密和解密，包括对文件头的认证和旧版本的兼容
+    #[test]
+    fn test_sealed() {
+        let passphrase = SecretString::from("correct horse");
+        let content = sealed::seal(SEALED_FORMAT, b"custom_id,uuid,enc_id\n", &passphrase).unwrap();
+        assert!(!content.contains("custom_id"));
+        let (format, plaintext) = sealed::open(&content, &passphrase).unwrap();
+        assert_eq!(format, SEALED_FORMAT);
+        assert_eq!(plaintext.as_slice(), b"custom_id,uuid,enc_id\n");
+
+        let error = sealed::open(&content, &SecretString::from("wrong")).unwrap_err();
+        assert_eq!(error.kind(), "crypto");
+        // 文件头参与认证，修改内容类型或派生参数都无法解密
+        let relabeled = content.replace(SEALED_FORMAT, "custom-rustdesk-identity");
+        assert_eq!(
+            sealed::open(&relabeled, &passphrase).unwrap_err().kind(),
+            "crypto"
+        );
+        let envelope: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let opslimit = envelope["opslimit"].as_u64().unwrap();
+        let weakened = content.replace(
+            &format!("\"opslimit\": {}", opslimit),
+            &format!("\"opslimit\": {}", opslimit - 1),
+        );
+        assert_eq!(
+            sealed::open(&weakened, &passphrase).unwrap_err().kind(),
+            "crypto"
+        );
+        let greedy = content.replace("\"memlimit\": ", "\"memlimit\": 99");
+        assert_eq!(
+            sealed::open(&greedy, &passphrase).unwrap_err().kind(),
+            "validation"
+        );
+        assert_eq!(
+            open_identity(&content, &passphrase).unwrap_err().kind(),
+            "validation"
+        );
+
+        // 只接受当前版本
+        let unsupported = content.replace("\"version\": 1", "\"version\": 2");
+        assert_eq!(
+            sealed::open(&unsupported, &passphrase).unwrap_err().kind(),
+            "validation"
+        );
+    }
+
+    /// 测试服务器地址和公钥验证
+    #[test]
+    fn test_server_option_validation() {
+        assert!(validate_server_address("rd.example.com").is_ok());
+        assert!(validate_server_address("rd.example.com:21116").is_ok());
+        assert!(validate_server_address("192.168.1.10:21117").is_ok());
+        assert!(validate_server_address("[::1]:21116").is_ok());
+        assert!(validate_server_address("fe80::1").is_ok());
+
+        assert!(validate_server_address("").is_err());
+        assert!(validate_server_address("bad host").is_err());
+        assert!(validate_server_address("-rd.example.com").is_err());
+        assert!(validate_server_address("rd.example.com:0").is_err());
+        assert!(validate_server_address("rd.example.com:65536").is_err());
+        assert!(validate_server_address("rd.example.com:").is_err());
+
+        assert!(validate_api_server("https://rd.example.com:21114").is_ok());
+        assert!(validate_api_server("http://rd.example.com/api").is_ok());
+        assert!(validate_api_server("rd.example.com:21114").is_err());
+        assert!(validate_api_server("https://:21114").is_err());
+
+        assert!(validate_public_key("AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=").is_ok());
+        assert!(validate_public_key("").is_err());
+        assert!(validate_public_key("AAECAwQF").is_err());
+        assert!(validate_public_key("not base64!").is_err());
+    }
+
+    /// 测试读取和修改 RustDesk2.toml 中的服务器选项
+    #[test]
+    fn test_server_settings_updates() {
+        let content = "rendezvous_server = 'rs-ny.rustdesk.com:21116'\n\n[options]\nkey = ''\nlocal-ip-addr = '10.0.0.5'\n";
+        let mut config = ConfigFile::parse(Path::new("RustDesk2.toml"), content).unwrap();
+        assert!(ServerSettings::from_config(&config).is_empty());
+
+        let settings = ServerSettings {
+            rendezvous_server: Some(" rd.example.com ".to_string()),
+            key: Some("AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=".to_string()),
+            ..ServerSettings::default()
+        };
+        let updates = settings.to_updates().unwrap();
+        assert_eq!(
+            updates[0],
+            ConfigUpdate::option("custom-rendezvous-server", "rd.example.com".to_string())
+        );
+        for update in &updates {
+            config.apply(update).unwrap();
+        }
+
+        let updated = config.content();
+        assert!(updated.contains("rendezvous_server = 'rs-ny.rustdesk.com:21116'"));
+        assert!(updated.contains("local-ip-addr = '10.0.0.5'"));
+        let reloaded = ServerSettings::from_config(&config);
+        assert_eq!(
+            reloaded.get(ServerOption::RendezvousServer),
+            Some("rd.example.com")
+        );
+        assert_eq!(reloaded.get(ServerOption::RelayServer), None);
+        assert_eq!(reloaded.key, settings.key);
+
+        // 没有 [options] 表时自动创建
+        let mut config = ConfigFile::parse(Path::new("RustDesk2.toml"), "nat_type = 1\n").unwrap();
+        config
+            .apply(&ConfigUpdate::option("relay-server", "rd.example.com".to_string()))
+            .unwrap();
+        assert_eq!(config.get_option("relay-server"), Some("rd.example.com"));
+        assert!(config.content().contains("[options]"));
+
+        // 内联表同样可以写入
+        let mut config =
+            ConfigFile::parse(Path::new("RustDesk2.toml"), "options = { a = 'b' }\n").unwrap();
+        config
+            .apply(&ConfigUpdate::option("relay-server", "rd.example.com".to_string()))
+            .unwrap();
+        assert_eq!(config.get_option("relay-server"), Some("rd.example.com"));
+        assert_eq!(config.get_option("a"), Some("b"));
+
+        // options 不是表时报告错误，不修改内容
+        let content = "options = 'x'\n";
+        let mut config = ConfigFile::parse(Path::new("RustDesk2.toml"), content).unwrap();
+        let update = ConfigUpdate::option("relay-server", "rd.example.com".to_string());
+        assert!(matches!(config.apply(&update), Err(AppError::ConfigError(_))));
+        assert!(!config.is_modified());
+        assert_eq!(config.content(), content);
+
+        let invalid = ServerSettings {
+            relay_server: Some("rd.example.com:99999".to_string()),
+            ..ServerSettings::default()
+        };
+        assert!(invalid.to_updates().is_err());
+    }
+
+    /// 测试配置字符串的编码和解码
+    #[test]
+    fn test_config_string_round_trip() {
+        let settings = ServerSettings {
+            rendezvous_server: Some("rd.example.com".to_string()),
+            relay_server: Some("rd.example.com:21117".to_string()),
+            api_server: Some("https://rd.example.com:21114".to_string()),
+            key: Some("AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=".to_string()),
+        };
+        let config_string = settings.to_config_string();
+        assert!(!config_string.contains(['=', '/', '+']));
+        assert_eq!(ServerSettings::from_config_string(&config_string).unwrap(), settings);
+
+        // RustDesk 界面导出的带填充的形式
+        let exported = "=0nI9gDaIR2d4dUYrJ0RYllUGVVToVkUBhHRPBTQEx0bRNUSjdmQGF1dBNURBFkI6ISeltmIsICNxETMyoTbvNmLlxGctFGel5CZy9yL6MHc0RHaiojIpBXYiwiI3ETMxIjOt92YuUGbw1WY4VmLkJnI6ISehxWZyJCLi02bj5SZsBXbhhXZuQmciojI0N3boJye";
+        assert_eq!(ServerSettings::from_config_string(exported).unwrap(), settings);
+
+        // 嵌入安装程序文件名，包括 Windows 为重名文件添加的后缀
+        let file_name = format!("rustdesk-licensed-{} (1).exe", config_string);
+        assert_eq!(ServerSettings::from_config_string(&file_name).unwrap(), settings);
+
+        // 空字段解码为未设置
+        let partial = ServerSettings {
+            rendezvous_server: Some("rd.example.com".to_string()),
+            ..ServerSettings::default()
+        };
+        let decoded = ServerSettings::from_config_string(&partial.to_config_string()).unwrap();
+        assert_eq!(decoded, partial);
+
+        assert!(ServerSettings::from_config_string("").is_err());
+        assert!(ServerSettings::from_config_string("not a config string").is_err());
+        assert!(ServerSettings::from_config_string("rustdesk-host=rd.example.com.exe").is_err());
+    }
+
+    /// 测试 HTTP API 的路由、令牌检查和错误映射
+    #[test]
+    fn test_api_handle_request() {
+        const TOKEN: &str = "secret";
+        const UUID: &str = "12345678-1234-1234-1234-123456789012";
+        let post = |path: &str, body: &str| {
+            let response = handle_request(
+                TOKEN,
+                None,
+                "POST",
+                path,
+                Some("Bearer secret"),
+                body.as_bytes(),
+            );
+            let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+            (response.status, json)
+        };
+
+        let health = handle_request(TOKEN, None, "GET", "/health", None, b"");
+        assert_eq!(health.status, 200);
+        for authorization in [None, Some("Bearer wrong"), Some("Basic secret")] {
+            let response = handle_request(TOKEN, None, "POST", "/encrypt", authorization, b"{}");
+            assert_eq!(response.status, 401);
+            assert!(response.body.contains("\"unauthorized\""));
+        }
+        let response =
+            handle_request(TOKEN, None, "POST", "/encrypt", Some("bearer secret"), b"{}");
+        assert_eq!(response.status, 400);
+
+        let (status, json) = post(
+            "/encrypt",
+            &format!(r#"{{"id":"TestComputer","uuid":"{}"}}"#, UUID),
+        );
+        assert_eq!(status, 200);
+        let enc_id = json["enc_id"].as_str().unwrap().to_string();
+        assert!(enc_id.starts_with("00"));
+        let (status, json) = post(
+            "/decrypt",
+            &format!(r#"{{"enc_id":"{}","uuid":"{}"}}"#, enc_id, UUID),
+        );
+        assert_eq!((status, json["id"].as_str()), (200, Some("TestComputer")));
+
+        // 错误类别与 AppError 对应
+        let (status, json) = post(
+            "/encrypt",
+            r#"{"id":"","uuid":"12345678-1234-1234-1234-123456789012"}"#,
+        );
+        assert_eq!(
+            (status, json["error"]["kind"].as_str()),
+            (400, Some("validation"))
+        );
+        let other = "87654321-4321-4321-4321-210987654321";
+        let (status, json) = post(
+            "/decrypt",
+            &format!(r#"{{"enc_id":"{}","uuid":"{}"}}"#, enc_id, other),
+        );
+        assert_eq!(
+            (status, json["error"]["kind"].as_str()),
+            (422, Some("crypto"))
+        );
+        let (status, json) = post("/decrypt", "not json");
+        assert_eq!(
+            (status, json["error"]["kind"].as_str()),
+            (400, Some("bad_request"))
+        );
+        assert_eq!(post("/unknown", "{}").0, 404);
+        let response =
+            handle_request(TOKEN, None, "GET", "/encrypt", Some("Bearer secret"), b"");
+        assert_eq!(response.status, 405);
+
+        let (status, json) = post("/validate", r#"{"id":"1 bad","enc_id":"Nhyr","uuid":"x"}"#);
+        assert_eq!((status, json["valid"].as_bool()), (200, Some(false)));
+        let fields: Vec<_> = json["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["field"].as_str().unwrap())
+            .collect();
+        assert_eq!(fields, ["enc_id", "uuid"]);
+        assert_eq!(json["warnings"].as_array().unwrap().len(), 3);
+        let (_, json) = post("/validate", r#"{"id":"TestComputer"}"#);
+        assert_eq!(json["valid"].as_bool(), Some(true));
+        assert_eq!(post("/validate", "{}").0, 400);
+
+        let (status, json) = post(
+            "/generate",
+            &format!(
+                r#"{{"uuid":"{}","prefix":"pc-","length":10,"count":3}}"#,
+                UUID
+            ),
+        );
+        assert_eq!(status, 200);
+        let ids = json["ids"].as_array().unwrap();
+        assert_eq!(ids.len(), 3);
+        for entry in ids {
+            let id = entry["id"].as_str().unwrap();
+            assert!(id.starts_with("pc-") && id.len() == 10);
+            assert!(custom_id_warnings(id).is_empty());
+            let (_, decrypted) = post(
+                "/decrypt",
+                &format!(
+                    r#"{{"enc_id":"{}","uuid":"{}"}}"#,
+                    entry["enc_id"].as_str().unwrap(),
+                    UUID
+                ),
+            );
+            assert_eq!(decrypted["id"].as_str(), Some(id));
+        }
+        let (_, json) = post("/generate", &format!(r#"{{"uuid":"{}"}}"#, UUID));
+        let id = json["ids"][0]["id"].as_str().unwrap();
+        assert!(id.len() == 9 && custom_id_warnings(id).is_empty());
+        for body in [r#""prefix":"1pc""#, r#""length":17"#, r#""count":0"#] {
+            let (status, json) = post("/generate", &format!(r#"{{"uuid":"{}",{}}}"#, UUID, body));
+            assert_eq!(
+                (status, json["error"]["kind"].as_str()),
+                (400, Some("validation"))
+            );
+        }
+    }
+
+    /// 测试 HTTP API 通过 ID 服务器查询 ID
+    #[test]
+    fn test_api_check_id() {
+        let address =
+            start_mock_rendezvous(&[("taken-online", true), ("taken-offline", false)], "");
+        let id_server = IdServer {
+            address,
+            key: String::new(),
+        };
+        let post = |id_server: Option<&IdServer>, body: &str| {
+            let response = handle_request(
+                "secret",
+                id_server,
+                "POST",
+                "/check-id",
+                Some("Bearer secret"),
+                body.as_bytes(),
+            );
+            let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+            (response.status, json)
+        };
+
+        for (id, status, available) in [
+            ("free-id", "available", true),
+            ("taken-offline", "offline", false),
+            ("taken-online", "online", false),
+        ] {
+            let (code, json) = post(Some(&id_server), &format!(r#"{{"id":"{}"}}"#, id));
+            assert_eq!(code, 200);
+            assert_eq!(json["id"].as_str(), Some(id));
+            assert_eq!(json["status"].as_str(), Some(status));
+            assert_eq!(json["available"].as_bool(), Some(available));
+        }
+
+        let (code, json) = post(Some(&id_server), r#"{"id":""}"#);
+        assert_eq!((code, json["error"]["kind"].as_str()), (400, Some("validation")));
+        let (code, json) = post(None, r#"{"id":"free-id"}"#);
+        assert_eq!((code, json["error"]["kind"].as_str()), (500, Some("config")));
+
+        // 绑定后立即释放的端口上没有服务在监听
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let unreachable = IdServer {
+            address: format!("127.0.0.1:{}", port),
+            key: String::new(),
+        };
+        let (code, json) = post(Some(&unreachable), r#"{"id":"free-id"}"#);
+        assert_eq!((code, json["error"]["kind"].as_str()), (502, Some("network")));
+    }
+
+    /// 启动进程内的模拟 hbbs，返回监听地址
+    ///
+    /// `peers` 为其他机器已注册的 ID 及其是否在线；`silent` 的打洞请求不会得到回应；
+    /// `key` 非空时要求打洞请求携带相同的公钥。注册 `too-frequent` 和 `id-exists`
+    /// 分别得到对应的错误，其他 ID 与首次注册的 UUID 绑定。
+    fn start_mock_rendezvous(peers: &'static [(&'static str, bool)], key: &'static str) -> String {
+        use prost::Message;
+        use std::io::Write;
+        use std::net::{TcpListener, UdpSocket};
+
+        // hbbs 在同一端口上同时监听 TCP 和 UDP
+        let (udp, tcp) = loop {
+            let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+            if let Ok(tcp) = TcpListener::bind(udp.local_addr().unwrap()) {
+                break (udp, tcp);
+            }
+        };
+        let address = tcp.local_addr().unwrap().to_string();
+        let is_online = |id: &str| peers.iter().any(|(peer, online)| *peer == id && *online);
+        let registered: HashMap<String, Vec<u8>> = peers
+            .iter()
+            .map(|(peer, _)| (peer.to_string(), b"other-machine".to_vec()))
+            .collect();
+
+        std::thread::spawn(move || {
+            for mut stream in tcp.incoming().flatten() {
+                let Ok(frame) = read_frame(&mut stream) else {
+                    continue;
+                };
+                let Ok(RendezvousMessage {
+                    union: Some(rendezvous_message::Union::OnlineRequest(request)),
+                }) = RendezvousMessage::decode(frame.as_slice())
+                else {
+                    continue;
+                };
+                let mut states = vec![0u8; request.peers.len().div_ceil(8)];
+                for (i, peer) in request.peers.iter().enumerate() {
+                    if is_online(peer) {
+                        states[i / 8] |= 0x01 << (7 - i % 8);
+                    }
+                }
+                let response = RendezvousMessage::new(
+                    rendezvous_message::Union::OnlineResponse(OnlineResponse { states }),
+                );
+                let _ = stream.write_all(&encode_frame(&response.encode_to_vec()));
+            }
+        });
+        std::thread::spawn(move || {
+            let mut registered = registered;
+            let mut buf = [0u8; 1024];
+            while let Ok((len, from)) = udp.recv_from(&mut buf) {
+                let request = match RendezvousMessage::decode(&buf[..len]) {
+                    Ok(RendezvousMessage {
+                        union: Some(rendezvous_message::Union::PunchHoleRequest(request)),
+                    }) => request,
+                    Ok(RendezvousMessage {
+                        union: Some(rendezvous_message::Union::RegisterPk(request)),
+                    }) => {
+                        if request.id == "silent" {
+                            continue;
+                        }
+                        let result = match request.id.as_str() {
+                            "too-frequent" => RegisterPkResult::TooFrequent,
+                            "id-exists" => RegisterPkResult::IdExists,
+                            id => match registered.get(id) {
+                                Some(uuid) if *uuid != request.uuid => {
+                                    RegisterPkResult::UuidMismatch
+                                }
+                                _ => {
+                                    registered.insert(request.id, request.uuid);
+                                    RegisterPkResult::Ok
+                                }
+                            },
+                        };
+                        let response = RendezvousMessage::new(
+                            rendezvous_message::Union::RegisterPkResponse(RegisterPkResponse {
+                                result: result as i32,
+                                keep_alive: 0,
+                            }),
+                        );
+                        let _ = udp.send_to(&response.encode_to_vec(), from);
+                        continue;
+                    }
+                    _ => continue,
+                };
+                if request.id == "silent" {
+                    continue;
+                }
+                let mut response = PunchHoleResponse::default();
+                if !key.is_empty() && request.licence_key != key {
+                    response.failure = PunchHoleFailure::LicenseMismatch as i32;
+                } else if is_online(&request.id) {
+                    response.socket_addr = vec![1, 2, 3, 4];
+                } else if registered.contains_key(&request.id) {
+                    response.failure = PunchHoleFailure::Offline as i32;
+                }
+                let response = RendezvousMessage::new(
+                    rendezvous_message::Union::PunchHoleResponse(response),
+                );
+                let _ = udp.send_to(&response.encode_to_vec(), from);
+            }
+        });
+        address
+    }
+
+    /// 测试 hbbs 协议的 TCP 分帧
+    #[test]
+    fn test_rendezvous_frames() {
+        for len in [0, 1, 0x3F, 0x40, 0x3FFF, 0x4000, 0x3F_FFFF, 0x40_0000] {
+            let payload = vec![0xAB; len];
+            let frame = encode_frame(&payload);
+            let head_len = frame.len() - len;
+            assert_eq!(head_len, (frame[0] & 0x3) as usize + 1);
+            if len <= 1024 * 1024 {
+                assert_eq!(read_frame(&mut frame.as_slice()).unwrap(), payload);
+            } else {
+                assert!(read_frame(&mut frame.as_slice()).is_err());
+            }
+        }
+        assert_eq!(encode_frame(b"abc"), [12, b'a', b'b', b'c']);
+
+        assert_eq!(with_default_port("rd.example.com"), "rd.example.com:21116");
+        assert_eq!(with_default_port("rd.example.com:2000"), "rd.example.com:2000");
+        assert_eq!(with_default_port("::1"), "[::1]:21116");
+        assert_eq!(with_default_port("[::1]"), "[::1]:21116");
+        assert_eq!(with_default_port("[::1]:2000"), "[::1]:2000");
+    }
+
+    /// 测试通过模拟的 hbbs 查询 ID 状态
+    #[test]
+    fn test_check_id_against_mock_server() {
+        let timeout = Duration::from_secs(2);
+        let server = start_mock_rendezvous(&[("online1", true), ("offline1", false)], "");
+        assert_eq!(check_id(&server, "free-id", "", timeout).unwrap(), IdStatus::Available);
+        assert_eq!(check_id(&server, "offline1", "", timeout).unwrap(), IdStatus::Offline);
+        assert_eq!(check_id(&server, "online1", "", timeout).unwrap(), IdStatus::Online);
+
+        let error = check_id(&server, "silent", "", Duration::from_millis(200)).unwrap_err();
+        assert!(matches!(error, AppError::NetworkError(_)));
+        assert!(error.message().contains("No reply"));
+
+        let keyed = start_mock_rendezvous(&[], "server-key");
+        assert_eq!(
+            check_id(&keyed, "free-id", "server-key", timeout).unwrap(),
+            IdStatus::Available
+        );
+        let error = check_id(&keyed, "free-id", "wrong", timeout).unwrap_err();
+        assert!(error.message().contains("rejected the key"));
+
+        // 没有服务在监听的端口
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let error = check_id(&closed.to_string(), "free-id", "", timeout).unwrap_err();
+        assert_eq!(error.kind(), "network");
+    }
+
+    /// 测试通过模拟的 hbbs 预先注册 ID
+    #[test]
+    fn test_register_pk_against_mock_server() {
+        let timeout = Duration::from_secs(2);
+        let server = start_mock_rendezvous(&[("taken1", false)], "");
+        let pk = [7u8; 32];
+
+        assert_eq!(check_id(&server, "new-id", "", timeout).unwrap(), IdStatus::Available);
+        register_pk(&server, "new-id", b"this-machine", &pk, timeout).unwrap();
+        assert_eq!(check_id(&server, "new-id", "", timeout).unwrap(), IdStatus::Offline);
+        // 同一台机器重复注册不是错误
+        register_pk(&server, "new-id", b"this-machine", &pk, timeout).unwrap();
+
+        let error = register_pk(&server, "taken1", b"this-machine", &pk, timeout).unwrap_err();
+        assert_eq!(error.kind(), "network");
+        assert!(error.message().contains("another machine"));
+        assert!(error.message().contains("\"taken1\""));
+        let error = register_pk(&server, "too-frequent", b"this-machine", &pk, timeout).unwrap_err();
+        assert!(error.message().contains("Wait a minute"));
+        let error = register_pk(&server, "id-exists", b"this-machine", &pk, timeout).unwrap_err();
+        assert!(error.message().contains("already exists"));
+
+        let error = register_pk(&server, "silent", b"this-machine", &pk, Duration::from_millis(200));
+        assert!(error.unwrap_err().message().contains("No reply"));
+    }
+
+    /// 测试由 MAC 地址生成默认 ID
+    #[test]
+    fn test_auto_id() {
+        let mac = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+        for input in [
+            "00:11:22:33:44:55",
+            "00-11-22-33-44-55",
+            "001122334455",
+            " 00:11:22:33:44:55 ",
+        ] {
+            assert_eq!(parse_mac_address(input).unwrap(), mac);
+        }
+        assert_eq!(auto_id(&mac), "36914261");
+        // 只保留低 29 位，前两个字节不参与计算
+        assert_eq!(auto_id(&[0xFF, 0xFF, 0xAA, 0xBB, 0xCC, 0xDD]), "180079837");
+        assert_eq!(auto_id(&parse_mac_address("aa:bb:cc:dd:ee:ff").unwrap()), "215871231");
+        assert_eq!(auto_id(&[0; 6]), "0");
+
+        for input in [
+            "",
+            "00:11:22:33:44",
+            "00:11:22:33:44:55:66",
+            "0:11:22:33:44:55",
+            "zz:11:22:33:44:55",
+            "00112233445",
+            "+01122334455",
+        ] {
+            assert!(parse_mac_address(input).is_err(), "{}", input);
+        }
+    }
+
+    /// 测试 UUID 作为密钥时的截断、填充和两种写法的转换
+    #[test]
+    fn test_uuid_key() {
+        let hyphenated = "12345678-1234-1234-1234-123456789012";
+        let raw = "12345678123412341234123456789012";
+        let enc_id = "Nhyr/aazYBq3jkSuNwA6trMEV2xPMoziEAJWog==";
+
+        let key = UuidKey::new(hyphenated);
+        assert_eq!((key.used, key.truncated(), key.padding()), (32, 4, 0));
+        let key = UuidKey::new(raw);
+        assert_eq!((key.used, key.truncated(), key.padding()), (32, 0, 0));
+        let key = UuidKey::new("abc");
+        assert_eq!((key.used, key.truncated(), key.padding()), (3, 0, 29));
+
+        // 与 RustDesk 一致：带连字符的 UUID 只有前 32 字节参与加密
+        assert_eq!(crypto::encrypt(b"TestComputer", hyphenated).unwrap(), enc_id);
+        assert_eq!(crypto::encrypt(b"TestComputer", &hyphenated[..32]).unwrap(), enc_id);
+        assert!(same_key(hyphenated, "12345678-1234-1234-1234-12345678ffff"));
+        assert!(!same_key(hyphenated, raw));
+        // 不足的部分以 0 填充，末尾的零字节不影响密钥
+        assert!(same_key("abc", "abc\0"));
+        assert_ne!(crypto::encrypt(b"TestComputer", raw).unwrap(), enc_id);
+
+        assert_eq!(UuidForm::detect(hyphenated), Some(UuidForm::Hyphenated));
+        assert_eq!(UuidForm::detect(raw), Some(UuidForm::Raw));
+        assert_eq!(UuidForm::detect("not-a-uuid"), None);
+        let converted = convert_uuid(&SecretString::from(raw), UuidForm::Hyphenated).unwrap();
+        assert_eq!(converted.expose(), hyphenated);
+        let converted = convert_uuid(&SecretString::from(hyphenated), UuidForm::Raw).unwrap();
+        assert_eq!(converted.expose(), raw);
+        let upper = SecretString::from("ABCDEF0012345678ABCDEF0012345678");
+        assert_eq!(
+            convert_uuid(&upper, UuidForm::Hyphenated).unwrap().expose(),
+            "ABCDEF00-1234-5678-ABCD-EF0012345678"
+        );
+        assert!(convert_uuid(&SecretString::from("not-a-uuid"), UuidForm::Raw).is_err());
+    }
+
+    /// 测试批量输入解析
+    #[test]
+    fn test_parse_batch() {
+        let entries = parse_batch(
+            "# comment\n\nplain\nwith,comma\nid,550e8400-e29b-41d4-a716-446655440000\n",
+        );
+        assert_eq!(entries.len(), 3);
+        assert_eq!((entries[0].line, entries[0].value.as_str()), (3, "plain"));
+        assert!(entries[0].uuid.is_none());
+        assert_eq!(entries[1].value, "with,comma");
+        assert!(entries[1].uuid.is_none());
+        assert_eq!(entries[2].value, "id");
+        assert_eq!(
+            entries[2].uuid.as_ref().map(|uuid| uuid.expose()),
+            Some("550e8400-e29b-41d4-a716-446655440000")
+        );
+
+        assert_eq!(csv_row(&["a", "b,c", "d\"e"]), "a,\"b,c\",\"d\"\"e\"");
+    }
+
+    /// 测试区域设置名称的规范化
+    #[test]
+    fn test_normalize_locale() {
+        let available = ["en", "zh", "zh-TW"];
+        let normalize = |tag| normalize_locale(tag, &available);
+
+        assert_eq!(normalize("zh_CN.UTF-8").as_deref(), Some("zh"));
+        assert_eq!(normalize("zh_TW.UTF-8").as_deref(), Some("zh-TW"));
+        assert_eq!(normalize("zh-tw").as_deref(), Some("zh-TW"));
+        assert_eq!(normalize("zh_HK.UTF-8").as_deref(), Some("zh-TW"));
+        assert_eq!(normalize("zh-Hant-TW").as_deref(), Some("zh-TW"));
+        assert_eq!(normalize("zh-Hans-CN").as_deref(), Some("zh"));
+        assert_eq!(normalize("en_US@euro").as_deref(), Some("en"));
+        assert_eq!(normalize("EN").as_deref(), Some("en"));
+
+        assert_eq!(normalize("fr_FR.UTF-8"), None);
+        assert_eq!(normalize(".UTF-8"), None);
+        assert_eq!(normalize(""), None);
+    }
+
+    const TUI_UUID: &str = "12345678-1234-1234-1234-123456789012";
+    const TUI_ENC_ID: &str = "00Nhyr/aazYBq3jkSuNwA6trMEV2xPMoziEAJWog==";
+
+    /// 依次输入文本中的每个字符
+    fn type_text(app: &mut TuiApp, text: &str) {
+        for c in text.chars() {
+            app.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    /// 测试终端界面的实时校验、差异预览和写入
+    #[test]
+    fn test_tui_edit_preview_and_save() {
+        let dir = std::env::temp_dir().join(format!("tui-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("RustDesk.toml");
+        fs::write(&path, "enc_id = '00old'\n").unwrap();
+
+        let mut app = TuiApp::new(vec![path.clone()], Some(SecretString::from(TUI_UUID)));
+        assert_eq!(app.focus, Focus::NewId);
+        assert!(app.id_feedback().is_none());
+        assert!(app.pending_config().unwrap().is_none());
+
+        type_text(&mut app, "bad\tid");
+        assert!(app.id_feedback().unwrap().is_err());
+        assert!(app.pending_config().is_err());
+        for _ in 0.."bad\tid".len() {
+            app.handle_key(KeyEvent::from(KeyCode::Backspace));
+        }
+
+        type_text(&mut app, "TestComputer");
+        assert!(app.id_feedback().unwrap().is_ok());
+        let diff = app.pending_config().unwrap().unwrap().diff();
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Removed("enc_id = '00old'".to_string()),
+                DiffLine::Added(format!("enc_id = \"{}\"", TUI_ENC_ID)),
+            ]
+        );
+
+        app.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(app.status, Some(Status::Info(_))));
+        assert!(app.saved);
+        assert!(fs::read_to_string(&path).unwrap().contains(TUI_ENC_ID));
+        let backups = fs::read_dir(&dir)
+            .unwrap()
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy().contains(".bak.")
+            })
+            .count();
+        assert_eq!(backups, 1);
+
+        // 再次写入相同的 ID 时不做修改
+        app.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert!(!app.pending_config().unwrap().unwrap().is_modified());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// 测试连续保存时每次都保留各自的备份，不覆盖原文件的备份，并保留原文件的权限
+    #[test]
+    fn test_config_backup_is_never_overwritten() {
+        let dir = std::env::temp_dir().join(format!("config-backup-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("RustDesk.toml");
+        let original = "enc_id = '00original'\n";
+        fs::write(&path, original).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        let mut config = ConfigFile::load(&path).unwrap();
+        config
+            .apply(&ConfigUpdate::top_level("enc_id", "00first".to_string()))
+            .unwrap();
+        let first = config.save(true).unwrap().unwrap();
+        let mut config = ConfigFile::load(&path).unwrap();
+        config
+            .apply(&ConfigUpdate::top_level("enc_id", "00second".to_string()))
+            .unwrap();
+        let second = config.save(true).unwrap().unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(fs::read_to_string(&first).unwrap(), original);
+        assert!(fs::read_to_string(&second).unwrap().contains("00first"));
+        assert!(fs::read_to_string(&path).unwrap().contains("00second"));
+        // 临时文件重命名后不留在目录中
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&path), 0o600);
+            assert_eq!(mode(&first), 0o600);
+            assert_eq!(mode(&second), 0o600);
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// 测试终端界面按 --uuid-form 转换给定的 UUID
+    #[test]
+    fn test_tui_initial_uuid_honours_form() {
+        let arg = UuidArg {
+            uuid: Some(SecretString::from("12345678123412341234123456789012")),
+            uuid_form: Some(UuidForm::Hyphenated),
+        };
+        let uuid = initial_uuid(&arg).unwrap().unwrap();
+        assert_eq!(uuid.expose(), "12345678-1234-1234-1234-123456789012");
+
+        let arg = UuidArg {
+            uuid: Some(SecretString::from("not-a-uuid")),
+            uuid_form: Some(UuidForm::Raw),
+        };
+        assert!(initial_uuid(&arg).is_err());
+    }
+
+    /// 测试终端界面的键盘导航和绘制
+    #[test]
+    fn test_tui_navigation_and_render() {
+        let dir = std::env::temp_dir().join(format!("tui-render-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let first = dir.join("first.toml");
+        let second = dir.join("second.toml");
+        fs::write(&first, format!("enc_id = '{}'\n", TUI_ENC_ID)).unwrap();
+        fs::write(&second, "key_confirmed = true\n").unwrap();
+
+        let mut app = TuiApp::new(vec![first, second], Some(SecretString::from(TUI_UUID)));
+        app.handle_key(KeyEvent::from(KeyCode::BackTab));
+        assert_eq!(app.focus, Focus::Files);
+
+        let render = |app: &TuiApp| {
+            let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+            terminal.draw(|frame| draw(frame, app)).unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            buffer
+                .content()
+                .chunks(buffer.area.width as usize)
+                .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let screen = render(&app);
+        assert!(screen.contains("first.toml"));
+        assert!(screen.contains("TestComputer"));
+        assert!(!screen.contains(TUI_UUID));
+
+        app.handle_key(KeyEvent::from(KeyCode::Down));
+        assert_eq!(app.selected, 1);
+        assert!(render(&app).contains("No enc_id field"));
+        app.handle_key(KeyEvent::from(KeyCode::Down));
+        assert_eq!(app.selected, 0);
+
+        app.handle_key(KeyEvent::from(KeyCode::Tab));
+        app.handle_key(KeyEvent::from(KeyCode::Tab));
+        assert_eq!(app.focus, Focus::Uuid);
+        assert!(render(&app).contains(TUI_UUID));
+
+        app.handle_key(KeyEvent::from(KeyCode::Esc));
+        assert!(app.quit);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// 测试提示选项的 Tab 补全
+    #[test]
+    fn test_choice_completion() {
+        let helper = ChoiceHelper::new(&["y", "n", "yes", "no"]);
+        let history = DefaultHistory::new();
+        let ctx = Context::new(&history);
+
+        let (start, candidates) = helper.complete("", 0, &ctx).unwrap();
+        assert_eq!(start, 0);
+        assert_eq!(candidates, ["y", "n", "yes", "no"]);
+
+        let (start, candidates) = helper.complete(" ye", 3, &ctx).unwrap();
+        assert_eq!(start, 1);
+        assert_eq!(candidates, ["yes"]);
+
+        assert!(helper.complete("x", 1, &ctx).unwrap().1.is_empty());
+    }
+
+    /// 测试敏感输入的遮盖显示
+    #[test]
+    fn test_secret_masking() {
+        let helper = ChoiceHelper::masked();
+        assert_eq!(helper.highlight(" pass 密码 ", 0), "*".repeat(11));
+        assert!(helper.highlight_char("pass", 4, CmdKind::Other));
+        assert!(!helper.highlight_char("pass", 4, CmdKind::MoveCursor));
+
+        let helper = ChoiceHelper::new(&[]);
+        assert_eq!(helper.highlight(" pass ", 0), " pass ");
+        assert!(!helper.highlight_char("pass", 4, CmdKind::Other));
+    }
 }
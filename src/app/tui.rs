@@ -0,0 +1,484 @@
+//! 全屏终端界面模块
+//!
+//! 左侧列出检测到的 RustDesk.toml，右侧显示当前保存的 ID、新 ID 与 UUID 表单及实时校验结果，
+//! 以及写入前的差异预览。所有操作都通过键盘完成。
+
+use crate::app::cli::{TuiArgs, UuidArg};
+use crate::app::uuid::given_uuid;
+use crate::core::config::{
+    detect_config_files, ConfigFile, DiffLine, ENC_ID_KEY, ENC_VERSION_PREFIX,
+};
+use crate::core::error::{AppError, AppResult};
+use crate::core::handler::{decrypt_value, display_error, perform_encrypt, EncryptResult};
+use crate::core::secret::{is_uuid_revealed, mask_uuid, SecretString};
+use crate::core::validation::{validate_custom_id, validate_uuid};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use rust_i18n::t;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use zeroize::Zeroizing;
+
+/// 获得键盘焦点的区域
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    /// 配置文件列表
+    Files,
+    /// 新 ID 输入框
+    NewId,
+    /// UUID 输入框
+    Uuid,
+}
+
+impl Focus {
+    /// 下一个区域
+    fn next(self) -> Self {
+        match self {
+            Focus::Files => Focus::NewId,
+            Focus::NewId => Focus::Uuid,
+            Focus::Uuid => Focus::Files,
+        }
+    }
+
+    /// 上一个区域
+    fn previous(self) -> Self {
+        match self {
+            Focus::Files => Focus::Uuid,
+            Focus::NewId => Focus::Files,
+            Focus::Uuid => Focus::NewId,
+        }
+    }
+}
+
+/// 状态栏消息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// 操作成功
+    Info(String),
+    /// 操作失败
+    Error(String),
+}
+
+/// 终端界面状态
+pub struct TuiApp {
+    /// 可选择的配置文件
+    pub configs: Vec<PathBuf>,
+    /// 当前选中的配置文件下标
+    pub selected: usize,
+    /// 当前选中配置文件的加载结果
+    config: Option<AppResult<ConfigFile>>,
+    /// 要写入的新 ID
+    pub new_id: String,
+    /// 加密使用的 UUID
+    pub uuid: Zeroizing<String>,
+    /// 获得焦点的区域
+    pub focus: Focus,
+    /// 最近一次操作的结果
+    pub status: Option<Status>,
+    /// 是否写入过配置文件
+    pub saved: bool,
+    /// 是否退出
+    pub quit: bool,
+}
+
+impl TuiApp {
+    /// 创建界面状态并加载第一个配置文件
+    pub fn new(configs: Vec<PathBuf>, uuid: Option<SecretString>) -> Self {
+        let mut app = TuiApp {
+            configs,
+            selected: 0,
+            config: None,
+            new_id: String::new(),
+            uuid: Zeroizing::new(uuid.map(|uuid| uuid.expose().to_string()).unwrap_or_default()),
+            focus: Focus::NewId,
+            status: None,
+            saved: false,
+            quit: false,
+        };
+        app.reload();
+        app
+    }
+
+    /// 重新读取当前选中的配置文件
+    fn reload(&mut self) {
+        self.config = self
+            .configs
+            .get(self.selected)
+            .map(|path| ConfigFile::load(path));
+    }
+
+    /// 当前输入的 UUID
+    fn uuid_secret(&self) -> SecretString {
+        SecretString::from(self.uuid.as_str())
+    }
+
+    /// 新 ID 的校验结果，尚未输入时返回 `None`
+    pub fn id_feedback(&self) -> Option<AppResult<()>> {
+        (!self.new_id.is_empty()).then(|| validate_custom_id(&self.new_id))
+    }
+
+    /// UUID 的校验结果
+    pub fn uuid_feedback(&self) -> AppResult<()> {
+        validate_uuid(&self.uuid)
+    }
+
+    /// 写入新 ID 后的配置文件，新 ID 尚未输入时返回 `None`
+    pub fn pending_config(&self) -> AppResult<Option<ConfigFile>> {
+        let config = match &self.config {
+            Some(Ok(config)) => config,
+            Some(Err(e)) => return Err(e.clone()),
+            None => return Err(AppError::ConfigError(t!("tui_no_config_selected"))),
+        };
+        let Some(id_feedback) = self.id_feedback() else {
+            return Ok(None);
+        };
+        id_feedback?;
+        self.uuid_feedback()?;
+
+        match perform_encrypt(&self.new_id, &self.uuid_secret()) {
+            EncryptResult::Success { encrypted_id, .. } => {
+                let mut config = config.clone();
+                config.set_str(ENC_ID_KEY, &format!("{}{}", ENC_VERSION_PREFIX, encrypted_id));
+                Ok(Some(config))
+            }
+            EncryptResult::Error(error_msg) => Err(AppError::CryptoError(error_msg)),
+        }
+    }
+
+    /// 将新 ID 写入当前配置文件并保留备份
+    pub fn save(&mut self) {
+        self.status = Some(match self.pending_config() {
+            Ok(Some(config)) if !config.is_modified() => Status::Info(t!("tui_no_changes")),
+            Ok(Some(config)) => match config.save(true) {
+                Ok(backup) => {
+                    self.saved = true;
+                    self.reload();
+                    let backup = backup.map(|path| path.display().to_string()).unwrap_or_default();
+                    Status::Info(t!(
+                        "tui_saved",
+                        path = config.path.display(),
+                        backup = backup
+                    ))
+                }
+                Err(e) => Status::Error(e.to_string()),
+            },
+            Ok(None) => Status::Error(t!("tui_preview_hint")),
+            Err(e) => Status::Error(e.to_string()),
+        });
+    }
+
+    /// 处理一次按键
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c' | 'q') if ctrl => self.quit = true,
+            KeyCode::Char('s') if ctrl => self.save(),
+            KeyCode::Tab => self.focus = self.focus.next(),
+            KeyCode::BackTab => self.focus = self.focus.previous(),
+            _ => match self.focus {
+                Focus::Files => self.handle_files_key(key.code),
+                Focus::NewId | Focus::Uuid => self.handle_field_key(key.code),
+            },
+        }
+    }
+
+    /// 在配置文件列表中处理按键
+    fn handle_files_key(&mut self, code: KeyCode) {
+        let count = self.configs.len();
+        if count == 0 {
+            return;
+        }
+        let selected = match code {
+            KeyCode::Up | KeyCode::Char('k') => (self.selected + count - 1) % count,
+            KeyCode::Down | KeyCode::Char('j') => (self.selected + 1) % count,
+            KeyCode::Home => 0,
+            KeyCode::End => count - 1,
+            KeyCode::Enter => {
+                self.focus = Focus::NewId;
+                return;
+            }
+            KeyCode::Char('q') => {
+                self.quit = true;
+                return;
+            }
+            _ => return,
+        };
+        if selected != self.selected {
+            self.selected = selected;
+            self.status = None;
+            self.reload();
+        }
+    }
+
+    /// 在输入框中处理按键
+    fn handle_field_key(&mut self, code: KeyCode) {
+        let field = match self.focus {
+            Focus::Uuid => &mut *self.uuid,
+            _ => &mut self.new_id,
+        };
+        match code {
+            KeyCode::Char(c) => {
+                field.push(c);
+            }
+            KeyCode::Backspace => {
+                field.pop();
+            }
+            KeyCode::Up => self.focus = self.focus.previous(),
+            KeyCode::Down => self.focus = self.focus.next(),
+            KeyCode::Enter => self.save(),
+            _ => {}
+        }
+    }
+}
+
+/// 打开全屏终端界面
+pub fn run_tui(args: &TuiArgs) -> ExitCode {
+    let configs = if args.configs.is_empty() {
+        detect_config_files()
+    } else {
+        args.configs.clone()
+    };
+    let uuid = match initial_uuid(&args.uuid) {
+        Ok(uuid) => uuid,
+        Err(e) => {
+            display_error(&e.to_string());
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut app = TuiApp::new(configs, uuid);
+
+    let mut terminal = match ratatui::try_init() {
+        Ok(terminal) => terminal,
+        Err(e) => {
+            display_error(&format!("{}: {}", t!("io_error"), e));
+            return ExitCode::FAILURE;
+        }
+    };
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+
+    if let Err(e) = result {
+        display_error(&format!("{}: {}", t!("io_error"), e));
+        return ExitCode::FAILURE;
+    }
+    if app.saved {
+        println!("{}", t!("restart_service_prompt"));
+    }
+    ExitCode::SUCCESS
+}
+
+/// 界面初始使用的 UUID：按 `--uuid-form` 转换给定的 UUID，未给定时使用检测到的机器 UUID
+pub fn initial_uuid(arg: &UuidArg) -> AppResult<Option<SecretString>> {
+    Ok(given_uuid(arg)?.or_else(|| machine_uid::get().ok().map(SecretString::new)))
+}
+
+/// 绘制界面并处理按键，直到用户退出
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut TuiApp) -> io::Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| draw(frame, app))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 获得焦点的区域使用高亮边框
+fn pane(title: String, focused: bool) -> Block<'static> {
+    let style = if focused {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    Block::bordered().title(title).border_style(style)
+}
+
+/// 校验结果对应的提示行
+fn feedback_line(feedback: Option<AppResult<()>>, hint: String) -> Line<'static> {
+    match feedback {
+        None => Line::styled(hint, Style::default().fg(Color::DarkGray)),
+        Some(Ok(())) => Line::styled(t!("tui_valid"), Style::default().fg(Color::Green)),
+        Some(Err(e)) => Line::styled(e.to_string(), Style::default().fg(Color::Red)),
+    }
+}
+
+/// 绘制整个界面
+pub fn draw(frame: &mut Frame, app: &TuiApp) {
+    let [body, status, keys] = Layout::vertical([
+        Constraint::Min(0),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [files, right] =
+        Layout::horizontal([Constraint::Percentage(35), Constraint::Min(0)]).areas(body);
+    let [current, form, preview] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Length(6),
+        Constraint::Min(0),
+    ])
+    .areas(right);
+
+    draw_files(frame, app, files);
+    draw_current(frame, app, current);
+    draw_form(frame, app, form);
+    draw_preview(frame, app, preview);
+
+    if let Some(message) = &app.status {
+        let line = match message {
+            Status::Info(text) => Line::styled(text.clone(), Style::default().fg(Color::Green)),
+            Status::Error(text) => Line::styled(text.clone(), Style::default().fg(Color::Red)),
+        };
+        frame.render_widget(Paragraph::new(line), status);
+    }
+    frame.render_widget(
+        Paragraph::new(Line::styled(
+            t!("tui_keys_help"),
+            Style::default().fg(Color::DarkGray),
+        )),
+        keys,
+    );
+}
+
+/// 绘制配置文件列表
+fn draw_files(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let block = pane(t!("tui_files_title"), app.focus == Focus::Files);
+    if app.configs.is_empty() {
+        let text = Paragraph::new(t!("tui_no_config_files"))
+            .wrap(Wrap { trim: true })
+            .block(block);
+        frame.render_widget(text, area);
+        return;
+    }
+
+    // 边框和选中标记各占两列
+    let width = usize::from(area.width.saturating_sub(4));
+    let items: Vec<ListItem> = app
+        .configs
+        .iter()
+        .map(|path| ListItem::new(elide_start(&path.display().to_string(), width)))
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// 文本过长时省略开头部分，保留文件名
+fn elide_start(text: &str, width: usize) -> String {
+    let count = text.chars().count();
+    if count <= width || width == 0 {
+        return text.to_string();
+    }
+    let tail: String = text.chars().skip(count - width + 1).collect();
+    format!("…{}", tail)
+}
+
+/// 绘制当前配置文件中保存的 ID
+fn draw_current(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let error = |text: String| Line::styled(text, Style::default().fg(Color::Red));
+    let lines = match &app.config {
+        None => vec![Line::raw(t!("tui_no_config_selected"))],
+        Some(Err(e)) => vec![error(e.to_string())],
+        Some(Ok(config)) => match config.get_str(ENC_ID_KEY) {
+            None => vec![Line::raw(t!("tui_enc_id_missing"))],
+            Some(enc_id) => {
+                let id = match app.uuid_feedback() {
+                    Ok(()) => match decrypt_value(enc_id, &app.uuid_secret()) {
+                        Ok(id) => Line::raw(t!("tui_current_id", id = id.expose())),
+                        Err(e) => error(e.to_string()),
+                    },
+                    Err(e) => error(e.to_string()),
+                };
+                vec![Line::raw(t!("tui_current_enc_id", value = enc_id)), id]
+            }
+        },
+    };
+
+    let block = pane(t!("tui_current_title"), false);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// 绘制新 ID 和 UUID 输入框
+fn draw_form(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let id_label = t!("tui_new_id_label");
+    let uuid_label = t!("tui_uuid_label");
+    let uuid_text = if app.focus == Focus::Uuid || is_uuid_revealed() {
+        app.uuid.to_string()
+    } else {
+        mask_uuid(&app.uuid)
+    };
+    let uuid_feedback = (!app.uuid.is_empty()).then(|| app.uuid_feedback());
+
+    let id_line = Line::from(vec![
+        Span::styled(id_label.clone(), Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(app.new_id.clone()),
+    ]);
+    let uuid_line = Line::from(vec![
+        Span::styled(uuid_label.clone(), Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(uuid_text),
+    ]);
+    let id_width = id_line.width();
+    let uuid_width = uuid_line.width();
+    let lines = vec![
+        id_line,
+        feedback_line(app.id_feedback(), t!("tui_id_hint")),
+        uuid_line,
+        feedback_line(uuid_feedback, t!("tui_uuid_hint")),
+    ];
+
+    let focused = matches!(app.focus, Focus::NewId | Focus::Uuid);
+    let block = pane(t!("tui_form_title"), focused);
+    let inner = block.inner(area);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+
+    // 将光标放在获得焦点的输入框末尾
+    let cursor = match app.focus {
+        Focus::NewId => Some((id_width, 0)),
+        Focus::Uuid => Some((uuid_width, 2)),
+        Focus::Files => None,
+    };
+    if let Some((x, y)) = cursor {
+        let x = inner.x + (x as u16).min(inner.width.saturating_sub(1));
+        frame.set_cursor_position(Position::new(x, inner.y + y));
+    }
+}
+
+/// 绘制写入前的差异预览
+fn draw_preview(frame: &mut Frame, app: &TuiApp, area: Rect) {
+    let lines = match app.pending_config() {
+        Ok(None) => vec![Line::styled(
+            t!("tui_preview_hint"),
+            Style::default().fg(Color::DarkGray),
+        )],
+        Ok(Some(config)) if !config.is_modified() => vec![Line::raw(t!("tui_no_changes"))],
+        Ok(Some(config)) => config
+            .diff()
+            .into_iter()
+            .map(|line| {
+                let color = match line {
+                    DiffLine::Removed(_) => Color::Red,
+                    DiffLine::Added(_) => Color::Green,
+                };
+                Line::styled(line.to_string(), Style::default().fg(color))
+            })
+            .collect(),
+        Err(e) => vec![Line::styled(e.to_string(), Style::default().fg(Color::Red))],
+    };
+
+    let block = pane(t!("tui_preview_title"), false);
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: false }).block(block),
+        area,
+    );
+}
@@ -1,38 +1,42 @@
+use crate::app::cli::UuidArg;
+use crate::app::input::{prompt_choice, prompt_secret};
+use crate::core::error::AppResult;
+use crate::core::handler::display_error;
+use crate::core::secret::{is_uuid_revealed, SecretString};
+use crate::core::uuid_key::convert_uuid;
 use machine_uid;
 use rust_i18n::t;
-use std::io::{self, Write};
 
 /// UUID 处理结果
 #[derive(Debug, Clone)]
 pub enum UuidResult {
     /// 成功获取 UUID
-    Success(String),
-    /// 用户取消操作
+    Success(SecretString),
+    /// 用户取消操作或输入结束
     Cancelled,
-    /// 发生错误
-    Error,
 }
 
 /// 自动检测并获取 UUID，支持用户确认
 pub fn get_uuid_with_confirmation() -> UuidResult {
-    match machine_uid::get() {
+    match machine_uid::get().map(SecretString::new) {
         Ok(machine_uuid) => {
             println!("{}", t!("auto_detected_uuid"));
-            println!("{}: {}", t!("detected_uuid_label"), machine_uuid);
+            println!("{}: {}", t!("detected_uuid_label"), machine_uuid.display_uuid());
+            if !is_uuid_revealed() {
+                println!("{}", t!("uuid_masked_hint"));
+            }
             println!();
 
             loop {
-                print!("{}", t!("confirm_uuid_prompt"));
-                if io::stdout().flush().is_err() {
-                    return UuidResult::Error;
-                }
-                let mut choice = String::new();
-                if io::stdin().read_line(&mut choice).is_err() {
-                    return UuidResult::Error;
-                }
-                let choice = choice.trim().to_lowercase();
+                let Some(choice) =
+                    prompt_choice(&t!("confirm_uuid_prompt"), &["y", "n", "yes", "no"])
+                else {
+                    println!();
+                    println!("{}", t!("operation_cancelled"));
+                    return UuidResult::Cancelled;
+                };
 
-                match choice.as_str() {
+                match choice.to_lowercase().as_str() {
                     "" | "y" | "yes" => {
                         return UuidResult::Success(machine_uuid);
                     }
@@ -66,15 +70,14 @@ fn get_manual_uuid_input() -> UuidResult {
     println!("{}", t!("get_uuid_macos"));
     println!();
 
-    print!("{}", t!("enter_uuid"));
-    if io::stdout().flush().is_err() {
-        return UuidResult::Error;
+    let uuid = prompt_secret(&t!("enter_uuid"));
+    if uuid.is_none() {
+        println!();
     }
-    let mut uuid = String::new();
-    if io::stdin().read_line(&mut uuid).is_err() {
-        return UuidResult::Error;
-    }
-    let uuid = uuid.trim().to_string();
+    // UUID 中的空白没有意义，去除复制时带入的首尾空白
+    let uuid = uuid
+        .map(|uuid| SecretString::from(uuid.expose().trim()))
+        .unwrap_or_default();
 
     if uuid.is_empty() {
         println!("{}", t!("operation_cancelled"));
@@ -85,9 +88,55 @@ fn get_manual_uuid_input() -> UuidResult {
 }
 
 /// 简化版本的 UUID 获取，用于交互模式
-pub fn get_uuid_input() -> String {
+pub fn get_uuid_input() -> SecretString {
     match get_uuid_with_confirmation() {
         UuidResult::Success(uuid) => uuid,
-        UuidResult::Cancelled | UuidResult::Error => String::new(),
+        UuidResult::Cancelled => SecretString::default(),
+    }
+}
+
+/// 解析命令使用的 UUID：优先使用参数指定的值，否则自动检测并请求确认
+///
+/// 用户取消或输入结束时返回 `None`，提示已在读取输入时显示。
+pub fn resolve_uuid(given: Option<&SecretString>) -> Option<SecretString> {
+    if let Some(uuid) = given {
+        return Some(uuid.clone());
+    }
+
+    match get_uuid_with_confirmation() {
+        UuidResult::Success(uuid) => Some(uuid),
+        UuidResult::Cancelled => None,
+    }
+}
+
+/// 参数指定的 UUID，指定了 `--uuid-form` 时转换为该写法
+pub fn given_uuid(arg: &UuidArg) -> AppResult<Option<SecretString>> {
+    match (&arg.uuid, arg.uuid_form) {
+        (Some(uuid), Some(form)) => convert_uuid(uuid, form).map(Some),
+        (uuid, _) => Ok(uuid.clone()),
+    }
+}
+
+/// 解析 `UuidArg` 参数，UUID 无法转换时显示错误并返回 `None`
+pub fn resolve_uuid_arg(arg: &UuidArg) -> Option<SecretString> {
+    match given_uuid(arg) {
+        Ok(uuid) => resolve_uuid(uuid.as_ref()),
+        Err(e) => {
+            display_error(&e.to_string());
+            None
+        }
+    }
+}
+
+/// 当前平台上机器 UUID 的来源
+pub fn uuid_source() -> String {
+    if cfg!(target_os = "windows") {
+        t!("uuid_source_windows")
+    } else if cfg!(target_os = "macos") {
+        t!("uuid_source_macos")
+    } else if cfg!(any(target_os = "freebsd", target_os = "dragonfly")) {
+        t!("uuid_source_bsd")
+    } else {
+        t!("uuid_source_linux")
     }
 }
@@ -0,0 +1,527 @@
+//! 本地 HTTP API 模块
+//!
+//! 供开通门户等内部系统通过 HTTP 调用加密、解密、验证和生成 ID，而不必调用命令行。
+//! 配置了 ID 服务器时，还可以向 hbbs 查询 ID 是否已被注册。
+//! 除 `GET /health` 外，所有请求都需要以 `Authorization: Bearer <令牌>` 提供访问令牌。
+//! 请求体和响应均为 JSON，错误以 `{"error": {"kind": ..., "message": ...}}` 返回，
+//! 其中 `kind` 与 `AppError` 的类别对应。
+
+use super::config::ENC_VERSION_PREFIX;
+use super::error::{AppError, AppResult};
+use super::handler::{decrypt_value, encrypt_id};
+use super::rendezvous::{check_id, IdStatus};
+use super::secret::SecretString;
+use super::validation::{
+    custom_id_warnings, validate_custom_id, validate_encrypted_id, validate_uuid,
+};
+use rust_i18n::t;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sodiumoxide::randombytes::{randombytes, randombytes_uniform};
+use std::io::Read;
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Header, Request, Response, Server};
+
+/// 默认监听地址，只接受本机连接
+pub const DEFAULT_BIND: &str = "127.0.0.1:8720";
+
+/// 提供访问令牌的环境变量
+pub const TOKEN_ENV: &str = "CUSTOM_RUSTDESK_API_TOKEN";
+
+/// 请求体的最大字节数
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+/// 一次最多生成的 ID 数量
+const MAX_GENERATE_COUNT: usize = 1000;
+
+/// 生成 ID 的默认长度，与 RustDesk 自动分配的 ID 相同
+const DEFAULT_GENERATE_LENGTH: usize = 9;
+
+/// RustDesk 接受的 ID 长度范围
+const MIN_ID_LENGTH: usize = 6;
+const MAX_ID_LENGTH: usize = 16;
+
+/// 生成 ID 时使用的字符
+const ID_LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const ID_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// 查询 ID 时等待 ID 服务器每次回复的时间
+const CHECK_ID_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 处理请求的线程数，等待 ID 服务器的 `/check-id` 请求不会阻塞其他请求
+const WORKERS: usize = 4;
+
+/// `POST /check-id` 查询的 ID 服务器
+#[derive(Debug, Clone)]
+pub struct IdServer {
+    /// 服务器地址，`host` 或 `host:port`
+    pub address: String,
+    /// 服务器的公钥，未设置时为空
+    pub key: String,
+}
+
+/// HTTP 响应的状态码和 JSON 内容
+#[derive(Debug)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+/// 请求失败的原因
+#[derive(Debug)]
+pub enum ApiError {
+    /// 缺少访问令牌或令牌错误
+    Unauthorized,
+    /// 没有该路径
+    NotFound,
+    /// 路径存在但不支持该方法
+    MethodNotAllowed,
+    /// 请求体超过长度限制
+    PayloadTooLarge,
+    /// 请求体不是合法的 JSON 或缺少字段
+    BadRequest(String),
+    /// 核心模块返回的错误
+    App(AppError),
+}
+
+impl ApiError {
+    /// HTTP 状态码
+    pub fn status(&self) -> u16 {
+        match self {
+            ApiError::Unauthorized => 401,
+            ApiError::NotFound => 404,
+            ApiError::MethodNotAllowed => 405,
+            ApiError::PayloadTooLarge => 413,
+            ApiError::BadRequest(_) => 400,
+            ApiError::App(AppError::ValidationError(_)) => 400,
+            ApiError::App(AppError::CryptoError(_)) => 422,
+            ApiError::App(AppError::ConfigError(_) | AppError::IoError(_)) => 500,
+            ApiError::App(AppError::NetworkError(_)) => 502,
+        }
+    }
+
+    /// 错误类别的名称
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::NotFound => "not_found",
+            ApiError::MethodNotAllowed => "method_not_allowed",
+            ApiError::PayloadTooLarge => "payload_too_large",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::App(error) => error.kind(),
+        }
+    }
+
+    /// 错误信息
+    pub fn message(&self) -> String {
+        match self {
+            ApiError::Unauthorized => t!("api_error_unauthorized"),
+            ApiError::NotFound => t!("api_error_not_found"),
+            ApiError::MethodNotAllowed => t!("api_error_method_not_allowed"),
+            ApiError::PayloadTooLarge => {
+                t!("api_error_payload_too_large", max = MAX_BODY_BYTES)
+            }
+            ApiError::BadRequest(msg) => msg.clone(),
+            ApiError::App(error) => error.message().to_string(),
+        }
+    }
+}
+
+impl From<AppError> for ApiError {
+    fn from(error: AppError) -> Self {
+        ApiError::App(error)
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: ErrorDetail,
+}
+
+#[derive(Serialize)]
+struct ErrorDetail {
+    kind: &'static str,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct HealthResponse {
+    status: &'static str,
+    version: &'static str,
+}
+
+#[derive(Deserialize)]
+struct EncryptRequest {
+    id: String,
+    uuid: SecretString,
+}
+
+#[derive(Serialize)]
+struct EncryptResponse {
+    id: String,
+    enc_id: String,
+}
+
+#[derive(Deserialize)]
+struct DecryptRequest {
+    enc_id: String,
+    uuid: SecretString,
+}
+
+#[derive(Serialize)]
+struct DecryptResponse {
+    enc_id: String,
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct ValidateRequest {
+    id: Option<String>,
+    enc_id: Option<String>,
+    uuid: Option<SecretString>,
+}
+
+#[derive(Serialize)]
+struct ValidateResponse {
+    valid: bool,
+    errors: Vec<FieldError>,
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct FieldError {
+    field: &'static str,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct GenerateRequest {
+    uuid: SecretString,
+    #[serde(default)]
+    prefix: String,
+    length: Option<usize>,
+    count: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct GenerateResponse {
+    ids: Vec<EncryptResponse>,
+}
+
+#[derive(Deserialize)]
+struct CheckIdRequest {
+    id: String,
+}
+
+#[derive(Serialize)]
+struct CheckIdResponse {
+    id: String,
+    status: IdStatus,
+    available: bool,
+}
+
+/// 生成随机的访问令牌
+pub fn generate_token() -> String {
+    // 初始化失败时 libsodium 仍会使用系统随机数源，忽略返回值
+    let _ = sodiumoxide::init();
+    randombytes(24)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// 在多个线程中处理服务器收到的请求，直到服务器关闭
+pub fn serve(server: &Server, token: &str, id_server: Option<&IdServer>) {
+    thread::scope(|scope| {
+        for _ in 0..WORKERS {
+            scope.spawn(|| {
+                for request in server.incoming_requests() {
+                    respond(request, token, id_server);
+                }
+            });
+        }
+    });
+}
+
+/// 处理并回应一个请求
+fn respond(mut request: Request, token: &str, id_server: Option<&IdServer>) {
+    let response = match read_body(&mut request) {
+        Ok(body) => {
+            let authorization = request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv("Authorization"))
+                .map(|header| header.value.as_str());
+            handle_request(
+                token,
+                id_server,
+                request.method().as_str(),
+                request.url(),
+                authorization,
+                &body,
+            )
+        }
+        Err(error) => error_response(&error),
+    };
+
+    // 客户端已断开时无法回应，忽略错误
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+    let _ = request.respond(
+        Response::from_string(response.body)
+            .with_status_code(response.status)
+            .with_header(content_type),
+    );
+}
+
+/// 读取请求体，超过长度限制时返回错误
+fn read_body(request: &mut Request) -> Result<Vec<u8>, ApiError> {
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return Err(ApiError::PayloadTooLarge);
+    }
+    Ok(body)
+}
+
+/// 处理一个请求，`authorization` 为 `Authorization` 请求头的值
+pub fn handle_request(
+    token: &str,
+    id_server: Option<&IdServer>,
+    method: &str,
+    url: &str,
+    authorization: Option<&str>,
+    body: &[u8],
+) -> ApiResponse {
+    let path = url.split('?').next().unwrap_or_default();
+    match route(token, id_server, method, path, authorization, body) {
+        Ok(body) => ApiResponse { status: 200, body },
+        Err(error) => error_response(&error),
+    }
+}
+
+fn route(
+    token: &str,
+    id_server: Option<&IdServer>,
+    method: &str,
+    path: &str,
+    authorization: Option<&str>,
+    body: &[u8],
+) -> Result<String, ApiError> {
+    // 健康检查不需要令牌，便于负载均衡器和监控探测
+    if path == "/health" {
+        require_method(method, "GET")?;
+        return to_json(&HealthResponse {
+            status: "ok",
+            version: env!("CARGO_PKG_VERSION"),
+        });
+    }
+    if !is_authorized(token, authorization) {
+        return Err(ApiError::Unauthorized);
+    }
+
+    match path {
+        "/encrypt" => {
+            require_method(method, "POST")?;
+            to_json(&handle_encrypt(parse_body(body)?)?)
+        }
+        "/decrypt" => {
+            require_method(method, "POST")?;
+            to_json(&handle_decrypt(parse_body(body)?)?)
+        }
+        "/validate" => {
+            require_method(method, "POST")?;
+            to_json(&handle_validate(parse_body(body)?)?)
+        }
+        "/generate" => {
+            require_method(method, "POST")?;
+            to_json(&handle_generate(parse_body(body)?)?)
+        }
+        "/check-id" => {
+            require_method(method, "POST")?;
+            to_json(&handle_check_id(id_server, parse_body(body)?)?)
+        }
+        _ => Err(ApiError::NotFound),
+    }
+}
+
+/// 检查 `Authorization: Bearer <令牌>`，以常数时间比较令牌
+fn is_authorized(token: &str, authorization: Option<&str>) -> bool {
+    let Some((scheme, credentials)) = authorization.and_then(|value| value.trim().split_once(' '))
+    else {
+        return false;
+    };
+    scheme.eq_ignore_ascii_case("Bearer")
+        && sodiumoxide::utils::memcmp(credentials.trim().as_bytes(), token.as_bytes())
+}
+
+fn require_method(method: &str, expected: &str) -> Result<(), ApiError> {
+    if method.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(ApiError::MethodNotAllowed)
+    }
+}
+
+fn parse_body<T: DeserializeOwned>(body: &[u8]) -> Result<T, ApiError> {
+    serde_json::from_slice(body)
+        .map_err(|e| ApiError::BadRequest(t!("api_error_invalid_json", error = e)))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, ApiError> {
+    serde_json::to_string(value).map_err(|e| ApiError::App(AppError::IoError(e.to_string())))
+}
+
+fn error_response(error: &ApiError) -> ApiResponse {
+    let body = ErrorBody {
+        error: ErrorDetail {
+            kind: error.kind(),
+            message: error.message(),
+        },
+    };
+    ApiResponse {
+        status: error.status(),
+        // 只包含字符串字段的结构体序列化不会失败
+        body: serde_json::to_string(&body).unwrap_or_default(),
+    }
+}
+
+fn handle_encrypt(request: EncryptRequest) -> AppResult<EncryptResponse> {
+    let enc_id = encrypt_id(&request.id, &request.uuid)?;
+    Ok(EncryptResponse {
+        id: request.id,
+        enc_id,
+    })
+}
+
+fn handle_decrypt(request: DecryptRequest) -> AppResult<DecryptResponse> {
+    let id = decrypt_value(request.enc_id.trim(), &request.uuid)?;
+    Ok(DecryptResponse {
+        enc_id: request.enc_id,
+        id: id.expose().to_string(),
+    })
+}
+
+/// 检查请求中给出的每个字段，ID 不符合 RustDesk 惯例时给出警告
+fn handle_validate(request: ValidateRequest) -> Result<ValidateResponse, ApiError> {
+    if request.id.is_none() && request.enc_id.is_none() && request.uuid.is_none() {
+        return Err(ApiError::BadRequest(t!("api_error_nothing_to_validate")));
+    }
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let mut check = |field: &'static str, result: AppResult<()>| {
+        if let Err(e) = result {
+            errors.push(FieldError {
+                field,
+                message: e.message().to_string(),
+            });
+        }
+    };
+
+    if let Some(id) = &request.id {
+        let result = validate_custom_id(id);
+        if result.is_ok() {
+            warnings = custom_id_warnings(id);
+        }
+        check("id", result);
+    }
+    if let Some(enc_id) = &request.enc_id {
+        check(
+            "enc_id",
+            match enc_id.trim().strip_prefix(ENC_VERSION_PREFIX) {
+                Some(payload) => validate_encrypted_id(payload),
+                None => Err(AppError::ValidationError(t!(
+                    "validation_error_enc_version"
+                ))),
+            },
+        );
+    }
+    if let Some(uuid) = &request.uuid {
+        check("uuid", validate_uuid(uuid.expose()));
+    }
+
+    Ok(ValidateResponse {
+        valid: errors.is_empty(),
+        errors,
+        warnings,
+    })
+}
+
+/// 生成符合 RustDesk 惯例的随机 ID 并加密，不保证与已部署的 ID 不重复
+fn handle_generate(request: GenerateRequest) -> AppResult<GenerateResponse> {
+    validate_uuid(request.uuid.expose())?;
+
+    let prefix = request.prefix.trim();
+    if !prefix.is_empty()
+        && (!prefix.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !prefix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+    {
+        return Err(AppError::ValidationError(t!("api_error_generate_prefix")));
+    }
+
+    let length = request.length.unwrap_or(DEFAULT_GENERATE_LENGTH);
+    let min_length = MIN_ID_LENGTH.max(prefix.len() + 1);
+    if !(min_length..=MAX_ID_LENGTH).contains(&length) {
+        return Err(AppError::ValidationError(t!(
+            "api_error_generate_length",
+            min = min_length,
+            max = MAX_ID_LENGTH
+        )));
+    }
+
+    let count = request.count.unwrap_or(1);
+    if !(1..=MAX_GENERATE_COUNT).contains(&count) {
+        return Err(AppError::ValidationError(t!(
+            "api_error_generate_count",
+            max = MAX_GENERATE_COUNT
+        )));
+    }
+
+    let ids = (0..count)
+        .map(|_| {
+            let id = random_id(prefix, length);
+            let enc_id = encrypt_id(&id, &request.uuid)?;
+            Ok(EncryptResponse { id, enc_id })
+        })
+        .collect::<AppResult<_>>()?;
+    Ok(GenerateResponse { ids })
+}
+
+/// 向配置的 ID 服务器查询 ID 是否已被注册
+fn handle_check_id(
+    id_server: Option<&IdServer>,
+    request: CheckIdRequest,
+) -> AppResult<CheckIdResponse> {
+    let id = request.id.trim();
+    validate_custom_id(id)?;
+    let Some(id_server) = id_server else {
+        return Err(AppError::ConfigError(t!("api_error_no_id_server")));
+    };
+    let status = check_id(&id_server.address, id, &id_server.key, CHECK_ID_TIMEOUT)?;
+    Ok(CheckIdResponse {
+        id: id.to_string(),
+        status,
+        available: status == IdStatus::Available,
+    })
+}
+
+/// 以 `prefix` 开头、总长度为 `length` 的随机 ID，没有前缀时以字母开头
+fn random_id(prefix: &str, length: usize) -> String {
+    let pick = |chars: &[u8]| chars[randombytes_uniform(chars.len() as u32) as usize] as char;
+    let mut id = prefix.to_string();
+    if id.is_empty() {
+        id.push(pick(ID_LETTERS));
+    }
+    while id.len() < length {
+        id.push(pick(ID_CHARS));
+    }
+    id
+}
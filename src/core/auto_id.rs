@@ -0,0 +1,50 @@
+//! 默认 ID 模块
+//!
+//! RustDesk.toml 中没有 ID，或 `enc_id` 无法用本机 UUID 解密时，RustDesk 会按
+//! hbb_common 的 `Config::get_auto_id` 由网卡的 MAC 地址重新生成数字 ID：
+//! 取后 4 个字节按大端序组成整数，再保留低 29 位。机器 UUID 不参与计算。
+//!
+//! ID 服务器以 `UUID_MISMATCH` 拒绝 ID 时，RustDesk 改用随机的 10 位数字 ID，无法预测。
+
+use super::error::{AppError, AppResult};
+use rust_i18n::t;
+
+/// RustDesk 保留的位数
+const AUTO_ID_MASK: u32 = 0x1FFF_FFFF;
+
+/// ID 服务器接受的最短 ID
+pub const MIN_ID_LEN: usize = 6;
+
+/// 解析 MAC 地址，接受以 `:` 或 `-` 分隔以及不分隔的 12 位十六进制形式
+pub fn parse_mac_address(input: &str) -> AppResult<[u8; 6]> {
+    let input = input.trim();
+    let invalid = || AppError::ValidationError(t!("validation_error_mac_address", mac = input));
+
+    let parts: Vec<&str> = if input.contains([':', '-']) {
+        input.split([':', '-']).collect()
+    } else if input.len() == 12 && input.is_ascii() {
+        (0..12).step_by(2).map(|i| &input[i..i + 2]).collect()
+    } else {
+        return Err(invalid());
+    };
+    if parts.len() != 6 {
+        return Err(invalid());
+    }
+
+    let mut mac = [0u8; 6];
+    for (byte, part) in mac.iter_mut().zip(parts) {
+        if part.len() != 2 || !part.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        *byte = u8::from_str_radix(part, 16).map_err(|_| invalid())?;
+    }
+    Ok(mac)
+}
+
+/// 计算 RustDesk 由 MAC 地址生成的默认 ID
+pub fn auto_id(mac: &[u8; 6]) -> String {
+    let id = mac[2..]
+        .iter()
+        .fold(0u32, |id, byte| (id << 8) | u32::from(*byte));
+    (id & AUTO_ID_MASK).to_string()
+}
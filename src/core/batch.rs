@@ -0,0 +1,68 @@
+//! 批量处理模块
+//!
+//! 解析批量输入（每行一个值，可选地以逗号附带 UUID），并生成 CSV 输出。
+
+use super::secret::SecretString;
+use super::validation::validate_uuid;
+
+/// 用口令加密的批量处理结果的内容类型
+pub const SEALED_FORMAT: &str = "custom-rustdesk-batch";
+
+/// 批量输入中的一行
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    /// 行号（从 1 开始）
+    pub line: usize,
+    /// 待处理的值（自定义 ID 或加密 ID）
+    pub value: String,
+    /// 该行指定的 UUID，未指定时使用默认 UUID
+    pub uuid: Option<SecretString>,
+}
+
+/// 解析批量输入，忽略空行和以 `#` 开头的注释行
+///
+/// 每行格式为 `value` 或 `value,uuid`。自定义 ID 本身可能包含逗号，
+/// 因此只有最后一个逗号之后是合法 UUID 时才将其视为 UUID 列。
+pub fn parse_batch(content: &str) -> Vec<BatchEntry> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, raw)| {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+
+            let (value, uuid) = match line.rsplit_once(',') {
+                Some((value, uuid)) if validate_uuid(uuid.trim()).is_ok() => {
+                    (value.trim(), Some(SecretString::from(uuid.trim())))
+                }
+                _ => (line, None),
+            };
+
+            Some(BatchEntry {
+                line: index + 1,
+                value: value.to_string(),
+                uuid,
+            })
+        })
+        .collect()
+}
+
+/// 按 CSV 规则转义字段
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// 生成一行 CSV
+pub fn csv_row(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,155 @@
+//! 身份迁移模块
+//!
+//! 更换硬件时，从旧机器的配置中解密 ID、永久密码、密钥对和服务器选项，
+//! 用口令加密保存为迁移包；在新机器上用口令打开迁移包，再以新机器的 UUID 重新加密写入。
+//!
+//! 迁移包的加密格式见 [`sealed`](super::sealed) 模块。
+
+use super::config::{
+    ConfigFile, ConfigUpdate, ENC_ID_KEY, ENC_VERSION_PREFIX, KEY_PAIR_KEY, OPTIONS_TABLE,
+    PASSWORD_KEY,
+};
+use super::error::{AppError, AppResult};
+use super::handler::{decrypt_value, encrypt_id, encrypt_value};
+use super::sealed;
+use super::secret::SecretString;
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use sodiumoxide::base64;
+use sodiumoxide::crypto::sign;
+use std::collections::BTreeMap;
+use zeroize::{Zeroize, Zeroizing};
+
+/// 迁移包格式标识
+pub const BUNDLE_FORMAT: &str = "custom-rustdesk-identity";
+
+/// 从旧机器迁移的身份
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Identity {
+    /// 解密后的自定义 ID
+    #[serde(default)]
+    pub id: Option<String>,
+    /// 解密后的永久密码
+    #[serde(default)]
+    pub password: Option<String>,
+    /// 密钥对的私钥，base64 编码
+    #[serde(default)]
+    pub secret_key: Option<String>,
+    /// 密钥对的公钥，base64 编码
+    #[serde(default)]
+    pub public_key: Option<String>,
+    /// RustDesk2.toml 中 `[options]` 表的所有字符串字段
+    #[serde(default)]
+    pub options: BTreeMap<String, String>,
+}
+
+impl Drop for Identity {
+    fn drop(&mut self) {
+        self.password.zeroize();
+        self.secret_key.zeroize();
+    }
+}
+
+impl Identity {
+    /// 从旧机器的配置中读取身份，加密字段用旧机器的 UUID 解密
+    pub fn from_configs(
+        config: &ConfigFile,
+        options: Option<&ConfigFile>,
+        uuid: &SecretString,
+    ) -> AppResult<Self> {
+        let mut identity = Identity::default();
+        if let Some(enc_id) = config.get_str(ENC_ID_KEY).filter(|value| !value.is_empty()) {
+            identity.id = Some(decrypt_value(enc_id, uuid)?.expose().to_string());
+        }
+        // 未加密的旧版永久密码原样迁移
+        match config.get_str(PASSWORD_KEY) {
+            Some(password) if password.starts_with(ENC_VERSION_PREFIX) => {
+                identity.password = Some(decrypt_value(password, uuid)?.expose().to_string());
+            }
+            Some(password) if !password.is_empty() => {
+                identity.password = Some(password.to_string());
+            }
+            _ => {}
+        }
+
+        let secret_key = Zeroizing::new(config.get_nested_bytes(KEY_PAIR_KEY, 0));
+        let public_key = config.get_nested_bytes(KEY_PAIR_KEY, 1);
+        if let (Some(secret_key), Some(public_key)) = (secret_key.as_ref(), public_key) {
+            if secret_key.len() == sign::SECRETKEYBYTES && public_key.len() == sign::PUBLICKEYBYTES
+            {
+                identity.secret_key = Some(base64::encode(secret_key, base64::Variant::Original));
+                identity.public_key = Some(base64::encode(public_key, base64::Variant::Original));
+            }
+        }
+
+        if let Some(options) = options {
+            identity.options = options.table_strs(OPTIONS_TABLE).into_iter().collect();
+        }
+        Ok(identity)
+    }
+
+    /// 是否包含密钥对
+    pub fn has_key_pair(&self) -> bool {
+        self.secret_key.is_some() && self.public_key.is_some()
+    }
+
+    /// 用新机器的 UUID 加密，生成对 RustDesk.toml 的修改
+    pub fn config_updates(&self, uuid: &SecretString) -> AppResult<Vec<ConfigUpdate>> {
+        let mut updates = Vec::new();
+        if let Some(id) = &self.id {
+            updates.push(ConfigUpdate::top_level(ENC_ID_KEY, encrypt_id(id, uuid)?));
+        }
+        if let Some(password) = &self.password {
+            let password = SecretString::from(password.as_str());
+            updates.push(ConfigUpdate::top_level(
+                PASSWORD_KEY,
+                encrypt_value(&password, uuid)?,
+            ));
+        }
+        Ok(updates)
+    }
+
+    /// 对 RustDesk2.toml 的修改
+    pub fn options_updates(&self) -> Vec<ConfigUpdate> {
+        self.options
+            .iter()
+            .map(|(key, value)| ConfigUpdate::option(key.clone(), value.clone()))
+            .collect()
+    }
+
+    /// 将密钥对写入 RustDesk.toml
+    pub fn apply_key_pair(&self, config: &mut ConfigFile) -> AppResult<()> {
+        let (Some(secret_key), Some(public_key)) = (&self.secret_key, &self.public_key) else {
+            return Ok(());
+        };
+        let invalid = || AppError::ValidationError(t!("bundle_error_format"));
+        let secret_key = Zeroizing::new(
+            base64::decode(secret_key, base64::Variant::Original).map_err(|_| invalid())?,
+        );
+        let public_key =
+            base64::decode(public_key, base64::Variant::Original).map_err(|_| invalid())?;
+        if secret_key.len() != sign::SECRETKEYBYTES || public_key.len() != sign::PUBLICKEYBYTES {
+            return Err(invalid());
+        }
+        config.set_nested_bytes(KEY_PAIR_KEY, &[&secret_key, &public_key]);
+        Ok(())
+    }
+}
+
+/// 用口令加密身份，返回迁移包的内容
+pub fn seal_identity(identity: &Identity, passphrase: &SecretString) -> AppResult<String> {
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(identity).map_err(|e| AppError::CryptoError(e.to_string()))?,
+    );
+    sealed::seal(BUNDLE_FORMAT, &plaintext, passphrase)
+}
+
+/// 用口令打开迁移包
+pub fn open_identity(content: &str, passphrase: &SecretString) -> AppResult<Identity> {
+    let invalid = || AppError::ValidationError(t!("bundle_error_format"));
+    let (format, plaintext) = sealed::open(content, passphrase)?;
+    if format != BUNDLE_FORMAT {
+        return Err(invalid());
+    }
+    serde_json::from_slice(&plaintext).map_err(|_| invalid())
+}
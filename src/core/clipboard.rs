@@ -0,0 +1,383 @@
+//! 剪切板模块
+//!
+//! 将剪切板访问抽象为 trait，提供系统剪切板 (arboard)、OSC 52 终端转义序列、
+//! 文件输出、空操作以及内存 (测试用) 等多种后端，并根据运行环境自动选择。
+
+use arboard::Clipboard;
+use rust_i18n::t;
+use sodiumoxide::base64;
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+#[cfg(test)]
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+/// 剪切板输出文件的环境变量，设置后结果写入该文件而不访问系统剪切板
+pub const CLIPBOARD_FILE_ENV: &str = "CUSTOM_RUSTDESK_CLIPBOARD_FILE";
+
+/// 当前使用的剪切板后端
+static BACKEND: Mutex<Option<Box<dyn ClipboardBackend + Send>>> = Mutex::new(None);
+
+/// 复制后自动清除剪切板的延迟
+static CLEAR_AFTER: Mutex<Option<Duration>> = Mutex::new(None);
+
+/// 等待执行的剪切板清除任务
+static PENDING_CLEAR: Mutex<Option<PendingClear>> = Mutex::new(None);
+
+/// 执行清除任务的后台线程
+static CLEAR_WORKERS: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// 剪切板清除任务
+struct PendingClear {
+    /// 本工具写入的内容
+    value: Zeroizing<String>,
+    /// 本工具第一次写入之前剪切板中的内容
+    previous: Option<Zeroizing<String>>,
+    /// 执行清除的时间
+    deadline: Instant,
+}
+
+/// 剪切板模式
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardMode {
+    /// 根据运行环境自动选择
+    Auto,
+    /// 系统剪切板
+    System,
+    /// OSC 52 终端转义序列（适用于 SSH/tmux）
+    Osc52,
+    /// 写入文件
+    File,
+    /// 不复制
+    None,
+}
+
+/// 剪切板后端 trait
+pub trait ClipboardBackend {
+    /// 将文本写入剪切板
+    fn set_text(&mut self, text: &str) -> Result<(), String>;
+
+    /// 读取剪切板中的文本，无法读取时返回 `None`
+    fn get_text(&mut self) -> Option<String> {
+        None
+    }
+
+    /// 清空剪切板
+    fn clear(&mut self) -> Result<(), String> {
+        self.set_text("")
+    }
+
+    /// 是否真正执行复制，禁用的后端不显示复制结果
+    fn is_enabled(&self) -> bool {
+        true
+    }
+}
+
+/// 系统剪切板后端
+pub struct SystemClipboard;
+
+impl ClipboardBackend for SystemClipboard {
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        let mut clipboard =
+            Clipboard::new().map_err(|e| format!("{}: {}", t!("clipboard_access_error"), e))?;
+        clipboard
+            .set_text(text)
+            .map_err(|e| format!("{}: {}", t!("clipboard_copy_error"), e))
+    }
+
+    fn get_text(&mut self) -> Option<String> {
+        Clipboard::new().ok()?.get_text().ok()
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        let mut clipboard =
+            Clipboard::new().map_err(|e| format!("{}: {}", t!("clipboard_access_error"), e))?;
+        clipboard
+            .clear()
+            .map_err(|e| format!("{}: {}", t!("clipboard_copy_error"), e))
+    }
+}
+
+/// OSC 52 终端剪切板后端，由终端模拟器负责写入本地剪切板
+pub struct Osc52Clipboard {
+    /// 是否需要 tmux 透传包装
+    pub tmux: bool,
+}
+
+impl Osc52Clipboard {
+    /// 根据环境变量判断是否运行在 tmux 中
+    pub fn from_env() -> Self {
+        Osc52Clipboard {
+            tmux: env::var_os("TMUX").is_some(),
+        }
+    }
+}
+
+impl ClipboardBackend for Osc52Clipboard {
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        let mut terminal = open_terminal();
+        terminal
+            .write_all(osc52_sequence(text, self.tmux).as_bytes())
+            .and_then(|_| terminal.flush())
+            .map_err(|e| format!("{}: {}", t!("clipboard_copy_error"), e))
+    }
+}
+
+/// 打开控制终端，转义序列不写入标准输出，以免混入 `--json` 或管道输出
+///
+/// 没有控制终端时使用标准错误。
+fn open_terminal() -> Box<dyn Write> {
+    #[cfg(unix)]
+    if let Ok(tty) = fs::OpenOptions::new().write(true).open("/dev/tty") {
+        return Box::new(tty);
+    }
+    Box::new(io::stderr())
+}
+
+/// 是否有可以接收 OSC 52 转义序列的终端
+fn has_terminal() -> bool {
+    #[cfg(unix)]
+    if fs::OpenOptions::new().write(true).open("/dev/tty").is_ok() {
+        return true;
+    }
+    io::stderr().is_terminal()
+}
+
+/// 生成写入剪切板的 OSC 52 转义序列
+pub fn osc52_sequence(text: &str, tmux: bool) -> String {
+    let payload = base64::encode(text, base64::Variant::Original);
+    let sequence = format!("\x1b]52;c;{}\x07", payload);
+    if tmux {
+        // tmux 需要 DCS 透传，并将内部的 ESC 加倍
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+/// 文件剪切板后端
+pub struct FileClipboard {
+    pub path: PathBuf,
+}
+
+impl ClipboardBackend for FileClipboard {
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        fs::write(&self.path, text).map_err(|e| format!("{}: {}", t!("clipboard_copy_error"), e))
+    }
+
+    fn get_text(&mut self) -> Option<String> {
+        fs::read_to_string(&self.path).ok()
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        match fs::remove_file(&self.path) {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("{}: {}", t!("clipboard_copy_error"), e)),
+        }
+    }
+}
+
+/// 空操作后端，禁用剪切板
+pub struct NoopClipboard;
+
+impl ClipboardBackend for NoopClipboard {
+    fn set_text(&mut self, _text: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn is_enabled(&self) -> bool {
+        false
+    }
+}
+
+/// 内存剪切板后端，供测试检查写入内容
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct MemoryClipboard {
+    pub contents: Arc<Mutex<Option<String>>>,
+}
+
+#[cfg(test)]
+impl ClipboardBackend for MemoryClipboard {
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        *self.contents.lock().unwrap() = Some(text.to_string());
+        Ok(())
+    }
+
+    fn get_text(&mut self) -> Option<String> {
+        self.contents.lock().unwrap().clone()
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        *self.contents.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+/// 根据模式创建剪切板后端
+pub fn create_backend(
+    mode: ClipboardMode,
+    file: Option<PathBuf>,
+) -> Result<Box<dyn ClipboardBackend + Send>, String> {
+    let file = file.or_else(|| env::var_os(CLIPBOARD_FILE_ENV).map(PathBuf::from));
+
+    let backend: Box<dyn ClipboardBackend + Send> = match mode {
+        ClipboardMode::System => Box::new(SystemClipboard),
+        ClipboardMode::Osc52 => Box::new(Osc52Clipboard::from_env()),
+        ClipboardMode::None => Box::new(NoopClipboard),
+        ClipboardMode::File => match file {
+            Some(path) => Box::new(FileClipboard { path }),
+            None => return Err(t!("clipboard_file_required")),
+        },
+        ClipboardMode::Auto => match file {
+            Some(path) => Box::new(FileClipboard { path }),
+            None => detect_backend(),
+        },
+    };
+    Ok(backend)
+}
+
+/// 自动检测适合当前环境的后端
+fn detect_backend() -> Box<dyn ClipboardBackend + Send> {
+    let over_ssh = env::var_os("SSH_TTY").is_some() || env::var_os("SSH_CONNECTION").is_some();
+    let headless = cfg!(all(unix, not(target_os = "macos")))
+        && env::var_os("DISPLAY").is_none()
+        && env::var_os("WAYLAND_DISPLAY").is_none();
+
+    if over_ssh || headless {
+        // 无法访问系统剪切板时，只有在终端中才能使用 OSC 52
+        if has_terminal() {
+            Box::new(Osc52Clipboard::from_env())
+        } else {
+            Box::new(NoopClipboard)
+        }
+    } else {
+        Box::new(SystemClipboard)
+    }
+}
+
+/// 设置全局剪切板后端
+pub fn set_backend(backend: Box<dyn ClipboardBackend + Send>) {
+    *BACKEND.lock().unwrap() = Some(backend);
+}
+
+/// 复制文本到剪切板，未设置后端时使用自动检测的后端
+///
+/// 返回 `Ok(false)` 表示剪切板已被禁用，没有执行复制。
+pub fn copy_to_clipboard(text: &str) -> Result<bool, String> {
+    let mut guard = BACKEND.lock().unwrap();
+    let backend = guard.get_or_insert_with(|| {
+        create_backend(ClipboardMode::Auto, None).unwrap_or_else(|_| Box::new(NoopClipboard))
+    });
+    if !backend.is_enabled() {
+        return Ok(false);
+    }
+
+    let clear_after = *CLEAR_AFTER.lock().unwrap();
+    let previous = clear_after.and_then(|_| backend.get_text().map(Zeroizing::new));
+    backend.set_text(text)?;
+    drop(guard);
+
+    if let Some(delay) = clear_after {
+        schedule_clear(text, previous, delay);
+    }
+    Ok(true)
+}
+
+/// 设置复制后自动清除剪切板的延迟，`None` 表示不清除
+pub fn set_clear_after(delay: Option<Duration>) {
+    *CLEAR_AFTER.lock().unwrap() = delay;
+}
+
+/// 安排在延迟之后恢复剪切板
+fn schedule_clear(value: &str, previous: Option<Zeroizing<String>>, delay: Duration) {
+    let mut pending = PENDING_CLEAR.lock().unwrap();
+    // 连续复制时保留最初的剪切板内容，避免把上一次复制的敏感值恢复回去
+    let previous = match pending.take() {
+        Some(earlier) => earlier.previous,
+        None => previous,
+    };
+    *pending = Some(PendingClear {
+        value: Zeroizing::new(value.to_string()),
+        previous,
+        deadline: Instant::now() + delay,
+    });
+    drop(pending);
+
+    // 每次安排都启动一个线程，先到期的线程执行任务，其余线程发现任务已取走后退出
+    CLEAR_WORKERS
+        .lock()
+        .unwrap()
+        .push(thread::spawn(run_clear_worker));
+}
+
+/// 后台线程：等待到期后恢复或清空剪切板
+fn run_clear_worker() {
+    loop {
+        let mut pending = PENDING_CLEAR.lock().unwrap();
+        let Some(deadline) = pending.as_ref().map(|task| task.deadline) else {
+            return;
+        };
+        let now = Instant::now();
+        if now < deadline {
+            drop(pending);
+            thread::sleep(deadline - now);
+            continue;
+        }
+
+        let task = pending.take().unwrap();
+        drop(pending);
+        restore_clipboard(task);
+        return;
+    }
+}
+
+/// 剪切板仍然是本工具写入的内容时，恢复之前的内容或清空剪切板
+///
+/// 无法读取剪切板的后端（如 OSC 52）无法确认内容，直接清空。
+fn restore_clipboard(task: PendingClear) {
+    let mut guard = BACKEND.lock().unwrap();
+    let Some(backend) = guard.as_mut() else {
+        return;
+    };
+    if let Some(current) = backend.get_text().map(Zeroizing::new) {
+        if *current != *task.value {
+            return;
+        }
+    }
+    let _ = match task.previous {
+        Some(previous) => backend.set_text(&previous),
+        None => backend.clear(),
+    };
+}
+
+/// 等待未完成的剪切板清除任务，程序退出前调用
+pub fn wait_for_pending_clear() {
+    let remaining = PENDING_CLEAR
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|task| task.deadline.saturating_duration_since(Instant::now()));
+    let Some(remaining) = remaining else {
+        return;
+    };
+
+    println!(
+        "{}",
+        t!(
+            "clipboard_clear_pending",
+            secs = remaining.as_secs_f64().ceil() as u64
+        )
+    );
+    let workers: Vec<_> = CLEAR_WORKERS.lock().unwrap().drain(..).collect();
+    for handle in workers {
+        let _ = handle.join();
+    }
+    println!("{}", t!("clipboard_cleared"));
+}
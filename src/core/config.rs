@@ -0,0 +1,432 @@
+//! RustDesk 配置文件模块
+//!
+//! 负责定位 RustDesk.toml，读取和修改其中的字段，并在写入前生成差异和备份。
+
+use super::error::{AppError, AppResult};
+use rust_i18n::t;
+use std::borrow::Cow;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use toml_edit::{value, Array, DocumentMut, Item, Table};
+
+/// 保存 ID 和永久密码的配置文件
+pub const CONFIG_FILE_NAME: &str = "RustDesk.toml";
+
+/// 保存服务器选项的配置文件
+pub const OPTIONS_FILE_NAME: &str = "RustDesk2.toml";
+
+/// 保存加密 ID 的字段
+pub const ENC_ID_KEY: &str = "enc_id";
+
+/// 保存加密永久密码的字段
+pub const PASSWORD_KEY: &str = "password";
+
+/// RustDesk.toml 中以机器 UUID 加密的字段
+pub const ENCRYPTED_KEYS: [&str; 2] = [ENC_ID_KEY, PASSWORD_KEY];
+
+/// 保存机器密钥对（私钥、公钥）的字段，格式为两个字节数组
+pub const KEY_PAIR_KEY: &str = "key_pair";
+
+/// RustDesk2.toml 中保存选项的表
+pub const OPTIONS_TABLE: &str = "options";
+
+/// RustDesk 加密值的版本前缀
+pub const ENC_VERSION_PREFIX: &str = "00";
+
+/// 当前平台上 RustDesk 配置目录可能所在的位置
+fn config_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    if cfg!(target_os = "windows") {
+        if let Some(appdata) = env::var_os("APPDATA") {
+            dirs.push(PathBuf::from(appdata).join(r"RustDesk\config"));
+        }
+        // 以服务方式 (--service) 运行时使用 LocalService 的配置
+        dirs.push(PathBuf::from(
+            r"C:\Windows\ServiceProfiles\LocalService\AppData\Roaming\RustDesk\config",
+        ));
+    } else if cfg!(target_os = "macos") {
+        if let Some(home) = env::var_os("HOME") {
+            dirs.push(PathBuf::from(home).join("Library/Preferences/com.carriez.RustDesk"));
+        }
+    } else {
+        if let Some(home) = env::var_os("HOME") {
+            dirs.push(PathBuf::from(home).join(".config/rustdesk"));
+        }
+        // 服务以 root 身份运行
+        let root = PathBuf::from("/root/.config/rustdesk");
+        if !dirs.contains(&root) {
+            dirs.push(root);
+        }
+    }
+
+    dirs
+}
+
+/// 当前平台上指定配置文件可能所在的位置
+pub fn default_paths(file_name: &str) -> Vec<PathBuf> {
+    config_dirs()
+        .into_iter()
+        .map(|dir| dir.join(file_name))
+        .collect()
+}
+
+/// 检测本机已存在的指定配置文件
+pub fn detect_files(file_name: &str) -> Vec<PathBuf> {
+    default_paths(file_name)
+        .into_iter()
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// 当前平台上 RustDesk.toml 可能所在的位置
+pub fn default_config_paths() -> Vec<PathBuf> {
+    default_paths(CONFIG_FILE_NAME)
+}
+
+/// 检测本机已存在的 RustDesk.toml
+pub fn detect_config_files() -> Vec<PathBuf> {
+    detect_files(CONFIG_FILE_NAME)
+}
+
+/// 对配置文件中一个字符串字段的修改
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigUpdate {
+    /// 字段所在的表，`None` 表示顶层
+    pub table: Option<&'static str>,
+    /// 字段名
+    pub key: Cow<'static, str>,
+    /// 新的值
+    pub value: String,
+}
+
+impl ConfigUpdate {
+    /// 修改顶层字段
+    pub fn top_level(key: &'static str, value: String) -> Self {
+        ConfigUpdate {
+            table: None,
+            key: Cow::Borrowed(key),
+            value,
+        }
+    }
+
+    /// 修改 `[options]` 表中的字段
+    pub fn option(key: impl Into<Cow<'static, str>>, value: String) -> Self {
+        ConfigUpdate {
+            table: Some(OPTIONS_TABLE),
+            key: key.into(),
+            value,
+        }
+    }
+}
+
+/// 已加载的配置文件，保留原始内容用于生成差异
+#[derive(Clone)]
+pub struct ConfigFile {
+    pub path: PathBuf,
+    original: String,
+    document: DocumentMut,
+}
+
+impl ConfigFile {
+    /// 读取并解析配置文件
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| AppError::IoError(format!("{}: {}", path.display(), e)))?;
+        Self::parse(path, &content)
+    }
+
+    /// 解析配置内容
+    pub fn parse(path: &Path, content: &str) -> AppResult<Self> {
+        let document = content.parse::<DocumentMut>().map_err(|e| {
+            AppError::ConfigError(format!(
+                "{}: {}",
+                t!("config_parse_error", path = path.display()),
+                e
+            ))
+        })?;
+        Ok(ConfigFile {
+            path: path.to_path_buf(),
+            original: content.to_string(),
+            document,
+        })
+    }
+
+    /// 读取顶层字符串字段
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.document.get(key).and_then(Item::as_str)
+    }
+
+    /// 设置顶层字符串字段，保留文件中的其他内容和格式
+    pub fn set_str(&mut self, key: &str, new_value: &str) {
+        if self.get_str(key) != Some(new_value) {
+            self.document[key] = value(new_value);
+        }
+    }
+
+    /// 读取顶层字节数组列表中的一项，例如 `key_pair = [[...], [...]]` 中的公钥
+    pub fn get_nested_bytes(&self, key: &str, index: usize) -> Option<Vec<u8>> {
+        self.document
+            .get(key)?
+            .as_array()?
+            .get(index)?
+            .as_array()?
+            .iter()
+            .map(|byte| byte.as_integer().and_then(|byte| u8::try_from(byte).ok()))
+            .collect()
+    }
+
+    /// 设置顶层字节数组列表，例如 `key_pair = [[...], [...]]`
+    pub fn set_nested_bytes(&mut self, key: &str, items: &[&[u8]]) {
+        let unchanged = items
+            .iter()
+            .enumerate()
+            .all(|(index, bytes)| self.get_nested_bytes(key, index).as_deref() == Some(*bytes))
+            && self.get_nested_bytes(key, items.len()).is_none();
+        if unchanged {
+            return;
+        }
+        let array: Array = items
+            .iter()
+            .map(|bytes| bytes.iter().map(|byte| i64::from(*byte)).collect::<Array>())
+            .collect();
+        self.document[key] = value(array);
+    }
+
+    /// 表中所有的字符串字段
+    pub fn table_strs(&self, table: &str) -> Vec<(String, String)> {
+        self.document
+            .get(table)
+            .and_then(Item::as_table_like)
+            .map(|table| {
+                table
+                    .iter()
+                    .filter_map(|(key, item)| Some((key.to_string(), item.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 读取表中的字符串字段
+    pub fn get_table_str(&self, table: &str, key: &str) -> Option<&str> {
+        self.document
+            .get(table)
+            .and_then(|table| table.get(key))
+            .and_then(Item::as_str)
+    }
+
+    /// 设置表中的字符串字段，表不存在时创建，同名字段不是表时返回错误
+    pub fn set_table_str(&mut self, table: &str, key: &str, new_value: &str) -> AppResult<()> {
+        if self.get_table_str(table, key) == Some(new_value) {
+            return Ok(());
+        }
+        if !self.document.contains_key(table) {
+            self.document.insert(table, Item::Table(Table::new()));
+        }
+        let Some(entries) = self.document[table].as_table_like_mut() else {
+            return Err(AppError::ConfigError(t!(
+                "config_not_a_table",
+                table = table,
+                path = self.path.display()
+            )));
+        };
+        match entries.get_mut(key) {
+            Some(item) => *item = value(new_value),
+            None => {
+                entries.insert(key, value(new_value));
+            }
+        }
+        Ok(())
+    }
+
+    /// 读取 `[options]` 表中的字符串字段
+    pub fn get_option(&self, key: &str) -> Option<&str> {
+        self.get_table_str(OPTIONS_TABLE, key)
+    }
+
+    /// 应用一项修改
+    pub fn apply(&mut self, update: &ConfigUpdate) -> AppResult<()> {
+        match update.table {
+            None => {
+                self.set_str(&update.key, &update.value);
+                Ok(())
+            }
+            Some(table) => self.set_table_str(table, &update.key, &update.value),
+        }
+    }
+
+    /// 修改后的完整内容
+    pub fn content(&self) -> String {
+        self.document.to_string()
+    }
+
+    /// 内容是否被修改
+    pub fn is_modified(&self) -> bool {
+        self.content() != self.original
+    }
+
+    /// 原始内容与修改后内容的差异
+    pub fn diff(&self) -> Vec<DiffLine> {
+        diff_lines(&self.original, &self.content())
+    }
+
+    /// 写入修改后的内容，`backup` 为真时先备份原文件，返回备份文件路径
+    pub fn save(&self, backup: bool) -> AppResult<Option<PathBuf>> {
+        let io_error = |e: std::io::Error| AppError::IoError(format!("{}: {}", self.path.display(), e));
+
+        let backup_path = if backup {
+            Some(create_backup(&self.path).map_err(io_error)?)
+        } else {
+            None
+        };
+
+        // 先写入临时文件再重命名，避免写入中断导致配置损坏
+        let permissions = fs::metadata(&self.path).map(|m| m.permissions()).ok();
+        let (mut tmp, tmp_path) =
+            create_sibling(&self.path, "tmp", permissions.as_ref()).map_err(io_error)?;
+        let written = tmp
+            .write_all(self.content().as_bytes())
+            .and_then(|()| match permissions {
+                Some(permissions) => tmp.set_permissions(permissions),
+                None => Ok(()),
+            })
+            .and_then(|()| {
+                drop(tmp);
+                fs::rename(&tmp_path, &self.path)
+            });
+        if let Err(e) = written {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(io_error(e));
+        }
+
+        Ok(backup_path)
+    }
+}
+
+/// 备份原文件，返回备份文件路径
+fn create_backup(path: &Path) -> io::Result<PathBuf> {
+    let mut source = fs::File::open(path)?;
+    let permissions = source.metadata()?.permissions();
+    let (mut backup, backup_path) = create_sibling(path, "bak", Some(&permissions))?;
+    io::copy(&mut source, &mut backup)?;
+    backup.set_permissions(permissions)?;
+    Ok(backup_path)
+}
+
+/// 在 `path` 旁创建名称不重复的新文件，返回文件及其路径
+///
+/// 文件名为 `<原文件名>.<kind>.<纳秒时间戳>`，同名文件已存在时追加序号。以 `create_new` 创建，
+/// 同一秒内多次保存也不会覆盖之前的文件，也不会跟随同名的符号链接。Unix 上创建时即使用
+/// 原文件的权限（没有原文件时只允许当前用户读写），写入密钥等内容前其他用户无法读取。
+fn create_sibling(
+    path: &Path,
+    kind: &str,
+    permissions: Option<&fs::Permissions>,
+) -> io::Result<(fs::File, PathBuf)> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(permissions.map_or(0o600, |permissions| permissions.mode() & 0o777));
+    }
+    #[cfg(not(unix))]
+    let _ = permissions;
+
+    for counter in 0u32.. {
+        let sibling = if counter == 0 {
+            path.with_file_name(format!("{}.{}.{}", file_name, kind, timestamp))
+        } else {
+            path.with_file_name(format!("{}.{}.{}.{}", file_name, kind, timestamp, counter))
+        };
+        match options.open(&sibling) {
+            Ok(file) => return Ok((file, sibling)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::from(io::ErrorKind::AlreadyExists))
+}
+
+/// 差异中的一行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// 删除的行
+    Removed(String),
+    /// 新增的行
+    Added(String),
+}
+
+impl fmt::Display for DiffLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffLine::Removed(line) => write!(f, "- {}", line),
+            DiffLine::Added(line) => write!(f, "+ {}", line),
+        }
+    }
+}
+
+impl DiffLine {
+    /// 隐藏给定字段的值，例如不在终端中显示 `key_pair` 中的私钥
+    pub fn redact(self, keys: &[&str]) -> Self {
+        let redact = |line: String| {
+            let key = line.split('=').next().unwrap_or_default().trim();
+            if line.contains('=') && keys.contains(&key) {
+                format!("{} = …", key)
+            } else {
+                line
+            }
+        };
+        match self {
+            DiffLine::Removed(line) => DiffLine::Removed(redact(line)),
+            DiffLine::Added(line) => DiffLine::Added(redact(line)),
+        }
+    }
+}
+
+/// 基于最长公共子序列计算两段文本之间按行的差异
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] 为 old[i..] 与 new[j..] 的最长公共子序列长度
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff
+}
@@ -1,22 +1,32 @@
 use sodiumoxide::base64;
 use sodiumoxide::crypto::secretbox;
+use zeroize::Zeroizing;
 
-pub fn decrypt(v: &[u8], uuid: &str) -> Result<Vec<u8>, ()> {
+/// 密钥长度（字节），更长的 UUID 只使用前面的部分
+pub const KEY_BYTES: usize = secretbox::KEYBYTES;
+
+pub fn decrypt(v: &[u8], uuid: &str) -> Result<Zeroizing<Vec<u8>>, ()> {
     base64::decode(v, base64::Variant::Original)
         .and_then(|v: Vec<u8>| symmetric_crypt(&v, uuid, false))
+        .map(Zeroizing::new)
 }
 
 pub fn encrypt(v: &[u8], uuid: &str) -> Result<String, ()> {
     symmetric_crypt(v, uuid, true).map(|v: Vec<u8>| base64::encode(v, base64::Variant::Original))
 }
 
+/// 由 UUID 得到实际使用的密钥
+pub fn effective_key(uuid: &str) -> secretbox::Key {
+    // 直接在密钥结构中构造密钥，密钥在释放时由 sodiumoxide 清零
+    let mut key = secretbox::Key([0; KEY_BYTES]);
+    // 与 RustDesk 一致：超出密钥长度的部分被截断，不足的部分以 0 填充
+    let len = uuid.len().min(KEY_BYTES);
+    key.0[..len].copy_from_slice(&uuid.as_bytes()[..len]);
+    key
+}
+
 pub fn symmetric_crypt(data: &[u8], uuid: &str, encrypt: bool) -> Result<Vec<u8>, ()> {
-    // 将字符串转换为 Vec<u8>
-    let mut keybuf: Vec<u8> = uuid.into();
-    // 调整 Vec 大小以适应密钥长度要求
-    keybuf.resize(secretbox::KEYBYTES, 0);
-    // 尝试将 Vec<u8> 转换为密钥结构，失败则返回错误
-    let key = secretbox::Key(keybuf.try_into().map_err(|_| ())?);
+    let key = effective_key(uuid);
     // 创建一个全0的 nonce
     let nonce = secretbox::Nonce([0; secretbox::NONCEBYTES]);
     // 根据 encrypt 参数选择加密或解密
@@ -0,0 +1,171 @@
+//! 部署脚本模块
+//!
+//! 为无法直接运行本工具的机器生成独立的部署脚本：停止 RustDesk 服务，
+//! 修改该机器上所有 RustDesk.toml 中的 `enc_id`（修改前备份），然后重新启动服务。
+
+use super::config::{ENC_ID_KEY, ENC_VERSION_PREFIX};
+
+/// 部署脚本格式
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptFormat {
+    /// Windows PowerShell 脚本
+    Powershell,
+    /// Linux/macOS 的 POSIX shell 脚本
+    Sh,
+}
+
+/// PowerShell 脚本模板，修改当前用户和 LocalService 的配置
+const POWERSHELL_TEMPLATE: &str = r#"# RustDesk ID deployment script generated by custom-rustdesk
+# Custom ID: @CUSTOM_ID@
+# Run this script as Administrator on the target machine.
+$ErrorActionPreference = 'Stop'
+
+$CustomId = @CUSTOM_ID_LITERAL@
+$EncIdLine = "@ENC_ID_KEY@ = '@ENC_ID@'"
+$ConfigFiles = @(
+    (Join-Path $env:APPDATA 'RustDesk\config\RustDesk.toml'),
+    'C:\Windows\ServiceProfiles\LocalService\AppData\Roaming\RustDesk\config\RustDesk.toml'
+)
+
+# RustDesk writes its configuration back when it exits, so stop it before patching
+$Service = Get-Service -Name 'RustDesk' -ErrorAction SilentlyContinue
+if ($Service -and $Service.Status -ne 'Stopped') {
+    Stop-Service -Name 'RustDesk' -Force
+}
+Get-Process -Name 'rustdesk' -ErrorAction SilentlyContinue | Stop-Process -Force
+
+foreach ($File in $ConfigFiles) {
+    if (-not (Test-Path -LiteralPath $File)) {
+        Write-Host "Skipped $File (not found)"
+        continue
+    }
+    # Never overwrite an earlier backup, it may hold the only copy of the original ID
+    $Stamp = [DateTimeOffset]::UtcNow.ToUnixTimeSeconds()
+    $Backup = "$File.bak.$Stamp"
+    $Counter = 0
+    while (Test-Path -LiteralPath $Backup) {
+        $Counter++
+        $Backup = "$File.bak.$Stamp.$Counter"
+    }
+    Copy-Item -LiteralPath $File -Destination $Backup
+    $Lines = @(Get-Content -LiteralPath $File)
+    if ($Lines -match '^@ENC_ID_KEY@\s*=') {
+        $Lines = $Lines | ForEach-Object { if ($_ -match '^@ENC_ID_KEY@\s*=') { $EncIdLine } else { $_ } }
+    } else {
+        $Lines = @($EncIdLine) + $Lines
+    }
+    [System.IO.File]::WriteAllLines($File, [string[]]$Lines)
+    Write-Host "Updated $File (backup: $Backup)"
+}
+
+if ($Service) {
+    Start-Service -Name 'RustDesk'
+}
+Write-Host "RustDesk ID set to $CustomId"
+"#;
+
+/// POSIX shell 脚本模板，修改当前用户和 root 的配置
+const SH_TEMPLATE: &str = r#"#!/bin/sh
+# RustDesk ID deployment script generated by custom-rustdesk
+# Custom ID: @CUSTOM_ID@
+# Run this script as root on the target machine.
+set -eu
+
+CUSTOM_ID=@CUSTOM_ID_LITERAL@
+ENC_ID_LINE="@ENC_ID_KEY@ = '@ENC_ID@'"
+
+if [ "$(uname -s)" = Darwin ]; then
+    CONFIG_DIRS="$HOME/Library/Preferences/com.carriez.RustDesk /var/root/Library/Preferences/com.carriez.RustDesk"
+    LAUNCH_DAEMON=/Library/LaunchDaemons/com.carriez.RustDesk_service.plist
+else
+    CONFIG_DIRS="$HOME/.config/rustdesk /root/.config/rustdesk"
+fi
+
+# RustDesk writes its configuration back when it exits, so stop it before patching
+stop_service() {
+    if [ -n "${LAUNCH_DAEMON:-}" ]; then
+        launchctl unload "$LAUNCH_DAEMON" 2>/dev/null || true
+    elif command -v systemctl >/dev/null 2>&1; then
+        systemctl stop rustdesk 2>/dev/null || true
+    fi
+}
+
+start_service() {
+    if [ -n "${LAUNCH_DAEMON:-}" ]; then
+        launchctl load -w "$LAUNCH_DAEMON" 2>/dev/null || true
+    elif command -v systemctl >/dev/null 2>&1; then
+        systemctl start rustdesk 2>/dev/null || true
+    fi
+}
+
+patch_config() {
+    file="$1/RustDesk.toml"
+    if [ ! -f "$file" ]; then
+        echo "Skipped $file (not found)"
+        return 0
+    fi
+    # Never overwrite an earlier backup, it may hold the only copy of the original ID.
+    # With noclobber the redirection fails if the name is taken, which reserves it atomically.
+    stamp=$(date +%s)
+    backup="$file.bak.$stamp"
+    counter=0
+    while ! (set -C; : > "$backup") 2>/dev/null; do
+        if [ ! -e "$backup" ]; then
+            echo "Cannot create backup $backup" >&2
+            exit 1
+        fi
+        counter=$((counter + 1))
+        backup="$file.bak.$stamp.$counter"
+    done
+    cp -p "$file" "$backup"
+    if grep -q '^@ENC_ID_KEY@[[:space:]]*=' "$file"; then
+        sed "s|^@ENC_ID_KEY@[[:space:]]*=.*|$ENC_ID_LINE|" "$backup" > "$file"
+    else
+        { echo "$ENC_ID_LINE"; cat "$backup"; } > "$file"
+    fi
+    echo "Updated $file (backup: $backup)"
+}
+
+# Run as root, $HOME is one of the fixed directories; patch each file only once
+UNIQUE_DIRS=
+for dir in $CONFIG_DIRS; do
+    case " $UNIQUE_DIRS " in
+        *" $dir "*) ;;
+        *) UNIQUE_DIRS="$UNIQUE_DIRS $dir" ;;
+    esac
+done
+
+stop_service
+for dir in $UNIQUE_DIRS; do
+    patch_config "$dir"
+done
+start_service
+echo "RustDesk ID set to $CUSTOM_ID"
+"#;
+
+/// 生成部署脚本，`encrypted_id` 为 `perform_encrypt` 返回的不含版本前缀的加密 ID
+pub fn render_script(format: ScriptFormat, custom_id: &str, encrypted_id: &str) -> String {
+    let (template, literal) = match format {
+        ScriptFormat::Powershell => (POWERSHELL_TEMPLATE, powershell_quote(custom_id)),
+        ScriptFormat::Sh => (SH_TEMPLATE, sh_quote(custom_id)),
+    };
+    // 加密 ID 只包含 base64 字符，可以直接写入单引号字符串和 sed 替换表达式
+    template
+        .replace("@CUSTOM_ID_LITERAL@", &literal)
+        .replace("@CUSTOM_ID@", custom_id)
+        .replace("@ENC_ID_KEY@", ENC_ID_KEY)
+        .replace(
+            "@ENC_ID@",
+            &format!("{}{}", ENC_VERSION_PREFIX, encrypted_id),
+        )
+}
+
+/// 转为 PowerShell 单引号字符串
+fn powershell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// 转为 POSIX shell 单引号字符串
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
@@ -0,0 +1,123 @@
+//! 幂等写入模块
+//!
+//! 供 Ansible、Salt 等配置管理工具反复调用：先解密配置文件中现有的 `enc_id`，
+//! 已经是期望的 ID 时不做任何修改，否则写入新的 `enc_id`，并以 JSON 报告是否有改动。
+
+use super::config::{ConfigFile, ENC_ID_KEY};
+use super::error::AppResult;
+use super::handler::decrypt_value;
+use super::secret::SecretString;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// 单个配置文件的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnsureStatus {
+    /// 已是期望的 ID，未修改
+    Unchanged,
+    /// 写入了新的 ID（预演模式下表示将会写入）
+    Changed,
+    /// 读取或写入失败
+    Failed,
+}
+
+/// 单个配置文件的报告
+#[derive(Debug, Serialize)]
+pub struct EnsureConfigReport {
+    /// 配置文件路径
+    pub path: PathBuf,
+    /// 处理结果
+    pub status: EnsureStatus,
+    /// 修改前的 ID，没有 `enc_id` 或无法解密时为 `None`
+    pub previous_id: Option<String>,
+    /// 备份文件路径
+    pub backup: Option<PathBuf>,
+    /// 失败原因
+    pub error: Option<String>,
+}
+
+/// 完整的报告
+#[derive(Debug, Serialize)]
+pub struct EnsureReport {
+    /// 是否有配置文件被修改
+    pub changed: bool,
+    /// 期望的 ID
+    pub id: String,
+    /// 是否为预演模式
+    pub dry_run: bool,
+    /// 每个配置文件的结果
+    pub configs: Vec<EnsureConfigReport>,
+    /// 无法开始处理的原因，例如没有可用的 UUID 或找不到配置文件
+    pub error: Option<String>,
+}
+
+impl EnsureReport {
+    /// 创建无法开始处理时的报告
+    pub fn failed(custom_id: &str, dry_run: bool, error: String) -> Self {
+        EnsureReport {
+            changed: false,
+            id: custom_id.to_string(),
+            dry_run,
+            configs: Vec::new(),
+            error: Some(error),
+        }
+    }
+
+    /// 是否存在错误
+    pub fn has_errors(&self) -> bool {
+        self.error.is_some()
+            || self
+                .configs
+                .iter()
+                .any(|config| config.status == EnsureStatus::Failed)
+    }
+}
+
+/// 确保配置文件中的 `enc_id` 解密后为 `custom_id`，需要修改时写入 `enc_id`
+pub fn ensure_config(
+    path: &Path,
+    custom_id: &str,
+    enc_id: &str,
+    uuid: &SecretString,
+    dry_run: bool,
+    backup: bool,
+) -> EnsureConfigReport {
+    let mut report = EnsureConfigReport {
+        path: path.to_path_buf(),
+        status: EnsureStatus::Failed,
+        previous_id: None,
+        backup: None,
+        error: None,
+    };
+
+    match ensure_enc_id(&mut report, custom_id, enc_id, uuid, dry_run, backup) {
+        Ok(status) => report.status = status,
+        Err(e) => report.error = Some(e.to_string()),
+    }
+    report
+}
+
+fn ensure_enc_id(
+    report: &mut EnsureConfigReport,
+    custom_id: &str,
+    enc_id: &str,
+    uuid: &SecretString,
+    dry_run: bool,
+    backup: bool,
+) -> AppResult<EnsureStatus> {
+    let mut config = ConfigFile::load(&report.path)?;
+    report.previous_id = config
+        .get_str(ENC_ID_KEY)
+        .and_then(|current| decrypt_value(current, uuid).ok())
+        .map(|id| id.expose().to_string());
+    if report.previous_id.as_deref() == Some(custom_id) {
+        return Ok(EnsureStatus::Unchanged);
+    }
+
+    config.set_str(ENC_ID_KEY, enc_id);
+    if !dry_run {
+        report.backup = config.save(backup)?;
+    }
+    Ok(EnsureStatus::Changed)
+}
@@ -7,15 +7,51 @@ use std::fmt;
 
 /// 应用程序主要错误类型
 #[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum AppError {
     /// 输入验证失败
     ValidationError(String),
+    /// 配置文件内容错误
+    ConfigError(String),
+    /// 文件读写失败
+    IoError(String),
+    /// 加密或解密失败
+    CryptoError(String),
+    /// 与 RustDesk 服务器通信失败
+    NetworkError(String),
 }
 
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             AppError::ValidationError(msg) => write!(f, "{}: {}", t!("validation_error"), msg),
+            AppError::ConfigError(msg) => write!(f, "{}: {}", t!("config_error"), msg),
+            AppError::IoError(msg) => write!(f, "{}: {}", t!("io_error"), msg),
+            AppError::CryptoError(msg) | AppError::NetworkError(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl AppError {
+    /// 错误类别的名称，用于 JSON 输出
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::ValidationError(_) => "validation",
+            AppError::ConfigError(_) => "config",
+            AppError::IoError(_) => "io",
+            AppError::CryptoError(_) => "crypto",
+            AppError::NetworkError(_) => "network",
+        }
+    }
+
+    /// 不含类别前缀的错误信息
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::ValidationError(msg)
+            | AppError::ConfigError(msg)
+            | AppError::IoError(msg)
+            | AppError::CryptoError(msg)
+            | AppError::NetworkError(msg) => msg,
         }
     }
 }
@@ -36,6 +72,13 @@ impl From<String> for AppError {
     }
 }
 
+/// 从 IO 错误转换
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        AppError::IoError(error.to_string())
+    }
+}
+
 /// 从 &str 转换为验证错误
 impl From<&str> for AppError {
     fn from(msg: &str) -> Self {
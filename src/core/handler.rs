@@ -1,7 +1,12 @@
+use super::clipboard::copy_to_clipboard;
+use super::config::ENC_VERSION_PREFIX;
 use super::crypto::{decrypt, encrypt};
-use super::validation::{validate_custom_id, validate_encrypted_id, validate_uuid};
+use super::error::{AppError, AppResult};
+use super::secret::{secret_from_utf8, SecretString};
+use super::validation::{
+    validate_custom_id, validate_encrypted_id, validate_password, validate_uuid,
+};
 use rust_i18n::t;
-use arboard::Clipboard;
 
 /// 加密操作结果
 #[derive(Debug, Clone)]
@@ -18,39 +23,39 @@ pub enum EncryptResult {
 pub enum DecryptResult {
     Success {
         encrypted_id: String,
-        decrypted_id: String,
+        decrypted_id: SecretString,
     },
     Error(String),
 }
 
 /// 执行加密操作
-pub fn perform_encrypt(custom_id: &str, uuid: &str) -> EncryptResult {
+pub fn perform_encrypt(custom_id: &str, uuid: &SecretString) -> EncryptResult {
     // 输入验证
     if let Err(e) = validate_custom_id(custom_id) {
         return EncryptResult::Error(format!("{}", e));
     }
 
-    if let Err(e) = validate_uuid(uuid) {
+    if let Err(e) = validate_uuid(uuid.expose()) {
         return EncryptResult::Error(format!("{}", e));
     }
 
-    match encrypt(custom_id.as_bytes(), uuid) {
+    match encrypt(custom_id.as_bytes(), uuid.expose()) {
         Ok(encrypted_string) => EncryptResult::Success {
             original_id: custom_id.to_string(),
             encrypted_id: encrypted_string,
         },
-        Err(_) => EncryptResult::Error(format!("{}", t!("encryption_error"))),
+        Err(_) => EncryptResult::Error(t!("encryption_error")),
     }
 }
 
 /// 执行解密操作
-pub fn perform_decrypt(enc_id: &str, uuid: &str) -> DecryptResult {
+pub fn perform_decrypt(enc_id: &str, uuid: &SecretString) -> DecryptResult {
     // 输入验证
     if let Err(e) = validate_encrypted_id(enc_id) {
         return DecryptResult::Error(format!("{}", e));
     }
 
-    if let Err(e) = validate_uuid(uuid) {
+    if let Err(e) = validate_uuid(uuid.expose()) {
         return DecryptResult::Error(format!("{}", e));
     }
 
@@ -58,8 +63,8 @@ pub fn perform_decrypt(enc_id: &str, uuid: &str) -> DecryptResult {
         return DecryptResult::Error(t!("invalid_encrypted_id_format"));
     }
 
-    match decrypt(&enc_id.as_bytes()[2..], uuid) {
-        Ok(decrypted_bytes) => match String::from_utf8(decrypted_bytes) {
+    match decrypt(&enc_id.as_bytes()[2..], uuid.expose()) {
+        Ok(decrypted_bytes) => match secret_from_utf8(decrypted_bytes) {
             Ok(decrypted_id) => DecryptResult::Success {
                 encrypted_id: enc_id.to_string(),
                 decrypted_id,
@@ -68,20 +73,61 @@ pub fn perform_decrypt(enc_id: &str, uuid: &str) -> DecryptResult {
                 DecryptResult::Error(format!("{}: {}", t!("invalid_decryption_result_error"), e))
             }
         },
-        Err(_) => DecryptResult::Error(format!("{}", t!("decryption_error"))),
+        Err(_) => DecryptResult::Error(t!("decryption_error")),
     }
 }
 
-/// 复制文本到剪切板
-fn copy_to_clipboard(text: &str) -> Result<(), String> {
-    match Clipboard::new() {
-        Ok(mut clipboard) => {
-            match clipboard.set_text(text) {
-                Ok(_) => Ok(()),
-                Err(e) => Err(format!("{}: {}", t!("clipboard_copy_error"), e)),
-            }
-        }
-        Err(e) => Err(format!("{}: {}", t!("clipboard_access_error"), e)),
+/// 解密带版本前缀的加密值
+pub fn decrypt_value(enc_value: &str, uuid: &SecretString) -> AppResult<SecretString> {
+    validate_uuid(uuid.expose())?;
+
+    let Some(payload) = enc_value.strip_prefix(ENC_VERSION_PREFIX) else {
+        return Err(AppError::ValidationError(t!("validation_error_enc_version")));
+    };
+    validate_encrypted_id(payload)?;
+
+    let decrypted_bytes = decrypt(payload.as_bytes(), uuid.expose())
+        .map_err(|_| AppError::CryptoError(t!("decryption_error")))?;
+    secret_from_utf8(decrypted_bytes).map_err(|e| {
+        AppError::CryptoError(format!("{}: {}", t!("invalid_decryption_result_error"), e))
+    })
+}
+
+/// 加密任意值，返回带版本前缀的完整密文，不检查值的内容
+pub fn encrypt_value(value: &SecretString, uuid: &SecretString) -> AppResult<String> {
+    validate_uuid(uuid.expose())?;
+
+    encrypt(value.expose().as_bytes(), uuid.expose())
+        .map(|encrypted| format!("{}{}", ENC_VERSION_PREFIX, encrypted))
+        .map_err(|_| AppError::CryptoError(t!("encryption_error")))
+}
+
+/// 加密自定义 ID，返回带版本前缀的完整 `enc_id`
+pub fn encrypt_id(custom_id: &str, uuid: &SecretString) -> AppResult<String> {
+    validate_custom_id(custom_id)?;
+    encrypt_value(&SecretString::from(custom_id), uuid)
+}
+
+/// 加密永久密码，返回带版本前缀的完整密文
+pub fn perform_encrypt_password(password: &SecretString, uuid: &SecretString) -> AppResult<String> {
+    validate_password(password.expose())?;
+    encrypt_value(password, uuid)
+}
+
+/// 解密永久密码
+pub fn perform_decrypt_password(
+    enc_password: &str,
+    uuid: &SecretString,
+) -> AppResult<SecretString> {
+    decrypt_value(enc_password.trim(), uuid)
+}
+
+/// 复制结果到剪切板并显示复制状态
+fn copy_and_report(text: &str) {
+    match copy_to_clipboard(text) {
+        Ok(true) => println!("{}", t!("clipboard_copy_success")),
+        Ok(false) => {}
+        Err(_) => println!("{}", t!("clipboard_copy_failed")),
     }
 }
 
@@ -103,10 +149,7 @@ pub fn display_encrypt_success(result: &EncryptResult) {
         
         // 尝试复制加密ID到剪切板（包含00前缀）
         let full_encrypted_id = format!("00{}", encrypted_id);
-        match copy_to_clipboard(&full_encrypted_id) {
-            Ok(_) => println!("{}", t!("clipboard_copy_success")),
-            Err(_) => println!("{}", t!("clipboard_copy_failed")),
-        }
+        copy_and_report(&full_encrypted_id);
         
         // 显示详细的使用说明
         println!();
@@ -135,20 +178,40 @@ pub fn display_decrypt_success(result: &DecryptResult) {
             t!(
                 "decrypt_success_with_id",
                 id = encrypted_id,
-                decrypted_id = decrypted_id
+                decrypted_id = decrypted_id.expose()
             )
         );
         
         // 尝试复制解密ID到剪切板
-        match copy_to_clipboard(decrypted_id) {
-            Ok(_) => println!("{}", t!("clipboard_copy_success")),
-            Err(_) => println!("{}", t!("clipboard_copy_failed")),
-        }
+        copy_and_report(decrypted_id.expose());
         
         println!("{}", t!("compare_id_prompt"));
     }
 }
 
+/// 显示密码加密结果
+pub fn display_password_encrypt_success(enc_password: &str) {
+    println!("{}", t!("encrypted_password_label", value = enc_password));
+    copy_and_report(enc_password);
+    println!("{}", t!("password_usage_hint"));
+}
+
+/// 显示密码解密结果
+pub fn display_password_decrypt_success(password: &SecretString) {
+    println!(
+        "{}",
+        t!("decrypted_password_label", value = password.expose())
+    );
+    copy_and_report(password.expose());
+}
+
+/// 显示生成的服务器配置字符串
+pub fn display_config_string(config_string: &str) {
+    println!("{}", t!("config_string_label", value = config_string));
+    copy_and_report(config_string);
+    println!("{}", t!("config_string_usage_hint", value = config_string));
+}
+
 /// 显示操作错误
 pub fn display_error(error_msg: &str) {
     println!("{}", error_msg);
@@ -0,0 +1,171 @@
+//! 诊断报告模块
+//!
+//! 收集配置文件中的 ID 与永久密码状态、使用的密钥等信息，
+//! 既可以显示为文本，也可以序列化为 JSON 附在支持工单中。
+
+use super::config::{ConfigFile, ENC_ID_KEY, ENC_VERSION_PREFIX, PASSWORD_KEY};
+use super::crypto::KEY_BYTES;
+use super::handler::decrypt_value;
+use super::secret::SecretString;
+use super::validation::{custom_id_warnings, validate_uuid};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// 完整的诊断报告
+#[derive(Debug, Serialize)]
+pub struct InspectReport {
+    /// 自动检测到的机器 UUID
+    pub machine_uuid: MachineUuidReport,
+    /// 解密使用的密钥，没有可用的 UUID 时为 `None`
+    pub key: Option<KeyReport>,
+    /// 是否自动检测配置文件
+    pub auto_detected: bool,
+    /// 自动检测时查找过的位置
+    pub searched_paths: Vec<PathBuf>,
+    /// 每个配置文件的检查结果
+    pub configs: Vec<ConfigReport>,
+}
+
+impl InspectReport {
+    /// 是否存在错误（没有可用的 UUID、没有配置文件、无法读取或无法解密 ID）
+    pub fn has_errors(&self) -> bool {
+        self.key.as_ref().is_none_or(|key| key.error.is_some())
+            || self.configs.is_empty()
+            || self
+                .configs
+                .iter()
+                .any(|config| config.error.is_some() || config.id_error.is_some())
+    }
+}
+
+/// 机器 UUID 的检测结果
+#[derive(Debug, Serialize)]
+pub struct MachineUuidReport {
+    /// 用于显示的 UUID，默认遮盖中间部分
+    pub uuid: Option<String>,
+    /// UUID 在当前平台上的来源
+    pub source: String,
+    /// 检测失败的原因
+    pub error: Option<String>,
+}
+
+/// UUID 的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyOrigin {
+    /// 自动检测到的机器 UUID
+    Machine,
+    /// 通过参数或手动输入指定，且与机器 UUID 不同
+    Given,
+}
+
+/// 解密使用的密钥
+#[derive(Debug, Serialize)]
+pub struct KeyReport {
+    /// UUID 的来源
+    pub origin: KeyOrigin,
+    /// 用于显示的 UUID，默认遮盖中间部分
+    pub uuid: String,
+    /// UUID 的字节长度
+    pub length: usize,
+    /// UUID 是否超出密钥长度而被截断
+    pub truncated: bool,
+    /// UUID 格式错误
+    pub error: Option<String>,
+}
+
+impl KeyReport {
+    /// 检查 UUID 作为密钥的情况
+    pub fn new(uuid: &SecretString, origin: KeyOrigin) -> Self {
+        let length = uuid.expose().len();
+        KeyReport {
+            origin,
+            uuid: uuid.display_uuid(),
+            length,
+            truncated: length > KEY_BYTES,
+            error: validate_uuid(uuid.expose()).err().map(|e| e.to_string()),
+        }
+    }
+}
+
+/// 永久密码的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordStatus {
+    /// 没有 password 字段
+    Missing,
+    /// 字段为空
+    Empty,
+    /// 没有版本前缀，以明文保存
+    Plaintext,
+    /// 已加密，但没有可用的 UUID 来验证
+    Encrypted,
+    /// 可以用当前 UUID 解密
+    Decrypts,
+    /// 无法用当前 UUID 解密
+    Undecryptable,
+}
+
+/// 单个配置文件的检查结果
+#[derive(Debug, Serialize)]
+pub struct ConfigReport {
+    /// 配置文件路径
+    pub path: PathBuf,
+    /// 读取或解析失败的原因
+    pub error: Option<String>,
+    /// 加密 ID
+    pub enc_id: Option<String>,
+    /// 解密得到的 ID
+    pub id: Option<String>,
+    /// 解密 ID 失败的原因
+    pub id_error: Option<String>,
+    /// ID 不符合 RustDesk 要求的警告
+    pub id_warnings: Vec<String>,
+    /// 永久密码的状态，读取失败时为 `None`
+    pub password: Option<PasswordStatus>,
+}
+
+/// 检查单个配置文件，`uuid` 为 `None` 时只报告字段是否存在
+pub fn inspect_config_file(path: &Path, uuid: Option<&SecretString>) -> ConfigReport {
+    let mut report = ConfigReport {
+        path: path.to_path_buf(),
+        error: None,
+        enc_id: None,
+        id: None,
+        id_error: None,
+        id_warnings: Vec::new(),
+        password: None,
+    };
+
+    let config = match ConfigFile::load(path) {
+        Ok(config) => config,
+        Err(e) => {
+            report.error = Some(e.to_string());
+            return report;
+        }
+    };
+
+    report.enc_id = config.get_str(ENC_ID_KEY).map(str::to_string);
+    if let (Some(enc_id), Some(uuid)) = (&report.enc_id, uuid) {
+        match decrypt_value(enc_id, uuid) {
+            Ok(id) => {
+                report.id_warnings = custom_id_warnings(id.expose());
+                report.id = Some(id.expose().to_string());
+            }
+            Err(e) => report.id_error = Some(e.to_string()),
+        }
+    }
+
+    report.password = Some(match config.get_str(PASSWORD_KEY) {
+        None => PasswordStatus::Missing,
+        Some("") => PasswordStatus::Empty,
+        Some(password) if !password.starts_with(ENC_VERSION_PREFIX) => PasswordStatus::Plaintext,
+        Some(password) => match uuid.map(|uuid| decrypt_value(password, uuid)) {
+            None => PasswordStatus::Encrypted,
+            Some(Ok(_)) => PasswordStatus::Decrypts,
+            Some(Err(_)) => PasswordStatus::Undecryptable,
+        },
+    });
+
+    report
+}
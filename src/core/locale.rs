@@ -0,0 +1,78 @@
+//! 界面语言模块
+//!
+//! 从命令行参数、环境变量或操作系统设置中确定界面语言，并将 `zh_CN.UTF-8`
+//! 之类的区域设置名称规范化为已提供翻译的语言代码。
+
+use std::env;
+
+/// 无法确定语言时使用的默认语言
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// `--lang` 的特殊取值，用于列出可用语言
+pub const LIST_LOCALES: &str = "list";
+
+/// 按 POSIX 优先级依次检查的环境变量
+const LOCALE_ENV_VARS: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// 已提供翻译的语言代码
+pub fn available_locales() -> Vec<&'static str> {
+    let mut locales = rust_i18n::available_locales!();
+    locales.sort_unstable();
+    locales
+}
+
+/// 将区域设置名称规范化为可用的语言代码
+///
+/// 忽略编码和修饰符（如 `.UTF-8`、`@euro`），依次尝试完整名称、`语言-地区`
+/// 和单独的语言部分，比较时不区分大小写。繁体中文地区和 `Hant` 文字会匹配 `zh-TW`。
+pub fn normalize_locale(tag: &str, available: &[&str]) -> Option<String> {
+    let tag = tag.split(['.', '@']).next().unwrap_or_default().trim();
+    if tag.is_empty() {
+        return None;
+    }
+
+    let full = tag.replace('_', "-");
+    let subtags: Vec<&str> = full.split('-').collect();
+    let language = subtags[0];
+
+    let mut candidates = vec![full.clone()];
+    if let Some(region) = subtags[1..].iter().find(|subtag| subtag.len() == 2) {
+        candidates.push(format!("{}-{}", language, region));
+    }
+    if language.eq_ignore_ascii_case("zh")
+        && subtags[1..].iter().any(|subtag| {
+            ["hant", "tw", "hk", "mo"].contains(&subtag.to_ascii_lowercase().as_str())
+        })
+    {
+        candidates.push("zh-TW".to_string());
+    }
+    candidates.push(language.to_string());
+
+    candidates.iter().find_map(|candidate| {
+        available
+            .iter()
+            .find(|locale| locale.eq_ignore_ascii_case(candidate))
+            .map(|locale| locale.to_string())
+    })
+}
+
+/// 从环境变量中读取区域设置名称
+///
+/// 第一个非空变量生效；`C` 和 `POSIX` 表示未设置语言。
+pub fn env_locale() -> Option<String> {
+    LOCALE_ENV_VARS
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .filter(|value| {
+            let name = value.split('.').next().unwrap_or_default();
+            name != "C" && name != "POSIX"
+        })
+}
+
+/// 从环境变量或操作系统设置中检测界面语言
+pub fn detect_locale(available: &[&str]) -> Option<String> {
+    env_locale()
+        .or_else(sys_locale::get_locale)
+        .and_then(|tag| normalize_locale(&tag, available))
+}
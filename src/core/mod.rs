@@ -1,4 +1,20 @@
+pub mod api;
+pub mod auto_id;
+pub mod batch;
+pub mod bundle;
+pub mod clipboard;
+pub mod config;
 pub mod crypto;
+pub mod deploy;
+pub mod ensure;
 pub mod error;
 pub mod handler;
+pub mod inspect;
+pub mod locale;
+pub mod options;
+pub mod rekey;
+pub mod rendezvous;
+pub mod sealed;
+pub mod secret;
+pub mod uuid_key;
 pub mod validation;
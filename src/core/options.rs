@@ -0,0 +1,192 @@
+//! RustDesk 服务器选项模块
+//!
+//! 自建服务器的 ID 服务器、中继服务器、API 服务器地址和服务器公钥保存在
+//! RustDesk2.toml 的 `[options]` 表中，每台部署的机器都需要设置。
+//! 这些设置也可以编码为 RustDesk 的配置字符串，粘贴到网络设置中或嵌入安装程序的文件名。
+
+use super::config::{ConfigFile, ConfigUpdate};
+use super::error::{AppError, AppResult};
+use super::validation::{validate_api_server, validate_public_key, validate_server_address};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use sodiumoxide::base64;
+
+/// 与自建服务器有关的选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerOption {
+    /// ID 服务器
+    RendezvousServer,
+    /// 中继服务器
+    RelayServer,
+    /// API 服务器
+    ApiServer,
+    /// 服务器公钥
+    Key,
+}
+
+impl ServerOption {
+    /// 所有选项，顺序与 RustDesk 网络设置中的一致
+    pub const ALL: [ServerOption; 4] = [
+        ServerOption::RendezvousServer,
+        ServerOption::RelayServer,
+        ServerOption::ApiServer,
+        ServerOption::Key,
+    ];
+
+    /// 选项在 `[options]` 表中的字段名
+    pub fn key(self) -> &'static str {
+        match self {
+            ServerOption::RendezvousServer => "custom-rendezvous-server",
+            ServerOption::RelayServer => "relay-server",
+            ServerOption::ApiServer => "api-server",
+            ServerOption::Key => "key",
+        }
+    }
+
+    /// 验证选项的值
+    pub fn validate(self, value: &str) -> AppResult<()> {
+        match self {
+            ServerOption::RendezvousServer | ServerOption::RelayServer => {
+                validate_server_address(value)
+            }
+            ServerOption::ApiServer => validate_api_server(value),
+            ServerOption::Key => validate_public_key(value),
+        }
+    }
+}
+
+/// 配置字符串中的 JSON 对象，字段名和顺序与 RustDesk 导出的一致
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ConfigStringFields {
+    host: String,
+    relay: String,
+    api: String,
+    key: String,
+}
+
+/// 自建服务器设置，未设置的选项为 `None`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerSettings {
+    pub rendezvous_server: Option<String>,
+    pub relay_server: Option<String>,
+    pub api_server: Option<String>,
+    pub key: Option<String>,
+}
+
+impl ServerSettings {
+    /// 读取配置文件中的设置，空值视为未设置
+    pub fn from_config(config: &ConfigFile) -> Self {
+        let mut settings = ServerSettings::default();
+        for option in ServerOption::ALL {
+            let value = config.get_option(option.key()).filter(|value| !value.is_empty());
+            *settings.slot_mut(option) = value.map(str::to_string);
+        }
+        settings
+    }
+
+    /// 选项的值
+    pub fn get(&self, option: ServerOption) -> Option<&str> {
+        match option {
+            ServerOption::RendezvousServer => self.rendezvous_server.as_deref(),
+            ServerOption::RelayServer => self.relay_server.as_deref(),
+            ServerOption::ApiServer => self.api_server.as_deref(),
+            ServerOption::Key => self.key.as_deref(),
+        }
+    }
+
+    fn slot_mut(&mut self, option: ServerOption) -> &mut Option<String> {
+        match option {
+            ServerOption::RendezvousServer => &mut self.rendezvous_server,
+            ServerOption::RelayServer => &mut self.relay_server,
+            ServerOption::ApiServer => &mut self.api_server,
+            ServerOption::Key => &mut self.key,
+        }
+    }
+
+    /// 是否没有设置任何选项
+    pub fn is_empty(&self) -> bool {
+        ServerOption::ALL
+            .iter()
+            .all(|option| self.get(*option).is_none())
+    }
+
+    /// 验证已设置的选项，并转换为对 RustDesk2.toml 的修改
+    pub fn to_updates(&self) -> AppResult<Vec<ConfigUpdate>> {
+        ServerOption::ALL
+            .iter()
+            .filter_map(|option| self.get(*option).map(|value| (*option, value.trim())))
+            .map(|(option, value)| {
+                option.validate(value)?;
+                Ok(ConfigUpdate::option(option.key(), value.to_string()))
+            })
+            .collect()
+    }
+
+    /// 编码为配置字符串：JSON 对象的 URL 安全 base64 编码（不含填充）倒序排列
+    ///
+    /// 不含填充和 `/` 的形式既可以粘贴到网络设置中，也可以嵌入安装程序的文件名。
+    pub fn to_config_string(&self) -> String {
+        let fields = ConfigStringFields {
+            host: self.rendezvous_server.clone().unwrap_or_default(),
+            relay: self.relay_server.clone().unwrap_or_default(),
+            api: self.api_server.clone().unwrap_or_default(),
+            key: self.key.clone().unwrap_or_default(),
+        };
+        // 只包含字符串字段的结构体序列化不会失败
+        let json = serde_json::to_vec(&fields).unwrap_or_default();
+        base64::encode(json, base64::Variant::UrlSafeNoPadding)
+            .chars()
+            .rev()
+            .collect()
+    }
+
+    /// 解码配置字符串，也接受 `rustdesk-licensed-<配置字符串>.exe` 形式的安装程序文件名
+    pub fn from_config_string(input: &str) -> AppResult<Self> {
+        let input: String = input.split_whitespace().collect();
+        let lower = input.to_ascii_lowercase();
+        let input = [".exe.exe", ".exe"]
+            .iter()
+            .find(|suffix| lower.ends_with(*suffix))
+            .map_or(input.as_str(), |suffix| &input[..input.len() - suffix.len()]);
+
+        // 与 RustDesk 一致：文件名以 `--` 分隔各部分，Windows 为重名文件添加的 "(1)" 等后缀被忽略
+        let name = input
+            .replace("-licensed---", "--")
+            .replace("-licensed--", "--")
+            .replace("-licensed-", "--");
+        std::iter::once(input)
+            .chain(name.split("--"))
+            .filter_map(|part| part.split('(').next())
+            .find_map(decode_config_string)
+            .ok_or_else(|| AppError::ValidationError(t!("validation_error_config_string")))
+    }
+}
+
+/// 解码单个配置字符串，兼容带或不带填充的标准和 URL 安全 base64
+fn decode_config_string(config_string: &str) -> Option<ServerSettings> {
+    if config_string.is_empty() {
+        return None;
+    }
+    let reversed: String = config_string.chars().rev().collect();
+    let json = [
+        base64::Variant::UrlSafeNoPadding,
+        base64::Variant::UrlSafe,
+        base64::Variant::Original,
+        base64::Variant::OriginalNoPadding,
+    ]
+    .into_iter()
+    .find_map(|variant| base64::decode(&reversed, variant).ok())?;
+    let fields: ConfigStringFields = serde_json::from_slice(&json).ok()?;
+
+    let non_empty = |value: String| {
+        let value = value.trim().to_string();
+        (!value.is_empty()).then_some(value)
+    };
+    Some(ServerSettings {
+        rendezvous_server: non_empty(fields.host),
+        relay_server: non_empty(fields.relay),
+        api_server: non_empty(fields.api),
+        key: non_empty(fields.key),
+    })
+}
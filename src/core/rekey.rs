@@ -0,0 +1,63 @@
+//! 重新加密模块
+//!
+//! 更换主板或重装系统后机器 UUID 会改变，RustDesk 无法再解密 RustDesk.toml 中以旧 UUID
+//! 加密的字段。这里用旧 UUID 解密每个加密字段，再用新 UUID 重新加密。
+
+use super::config::{ConfigFile, ConfigUpdate, ENCRYPTED_KEYS, ENC_VERSION_PREFIX};
+use super::error::{AppError, AppResult};
+use super::handler::{decrypt_value, encrypt_value};
+use super::secret::SecretString;
+use rust_i18n::t;
+
+/// 单个字段的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RekeyStatus {
+    /// 已用新 UUID 重新加密
+    Rekeyed,
+    /// 已经可以用新 UUID 解密，无需修改
+    AlreadyRekeyed,
+}
+
+/// 对单个配置文件的重新加密计划
+#[derive(Debug, Default)]
+pub struct RekeyPlan {
+    /// 每个加密字段的处理结果
+    pub fields: Vec<(&'static str, RekeyStatus)>,
+    /// 需要写入的字段
+    pub updates: Vec<ConfigUpdate>,
+}
+
+/// 计算配置文件中每个加密字段的新值
+///
+/// 不带版本前缀的字段未加密，保持不变。任何字段既不能用旧 UUID 也不能用新 UUID
+/// 解密时返回错误，整个文件都不会被修改。
+pub fn plan_rekey(
+    config: &ConfigFile,
+    from_uuid: &SecretString,
+    to_uuid: &SecretString,
+) -> AppResult<RekeyPlan> {
+    let mut plan = RekeyPlan::default();
+    for key in ENCRYPTED_KEYS {
+        let Some(value) = config
+            .get_str(key)
+            .filter(|value| value.starts_with(ENC_VERSION_PREFIX))
+        else {
+            continue;
+        };
+
+        match decrypt_value(value, from_uuid) {
+            Ok(plain) => {
+                let value = encrypt_value(&plain, to_uuid)?;
+                plan.fields.push((key, RekeyStatus::Rekeyed));
+                plan.updates.push(ConfigUpdate::top_level(key, value));
+            }
+            Err(_) if decrypt_value(value, to_uuid).is_ok() => {
+                plan.fields.push((key, RekeyStatus::AlreadyRekeyed));
+            }
+            Err(_) => {
+                return Err(AppError::CryptoError(t!("rekey_error_field", key = key)));
+            }
+        }
+    }
+    Ok(plan)
+}
@@ -0,0 +1,439 @@
+//! RustDesk 会合服务器（hbbs）协议模块
+//!
+//! 实现与自建 hbbs 通信所需的 protobuf 消息和 TCP 分帧，用于在部署前查询 ID 是否已被占用，
+//! 以及在客户端启动之前为机器预先注册 ID。
+//! 消息定义与 RustDesk 的 rendezvous.proto 保持一致，只保留用到的消息和字段。
+
+use super::error::{AppError, AppResult};
+use prost::Message;
+use rust_i18n::t;
+use serde::Serialize;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// hbbs 默认监听的 TCP/UDP 端口
+pub const RENDEZVOUS_PORT: u16 = 21116;
+
+/// 单个 TCP 帧的最大长度，超过时视为协议错误
+const MAX_FRAME_BYTES: usize = 1024 * 1024;
+
+/// 没有收到响应时重发 UDP 请求的间隔
+const UDP_RESEND_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 打洞请求，查询 ID 时只会在对方离线或不存在时发送
+#[derive(Clone, PartialEq, Message)]
+pub struct PunchHoleRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(int32, tag = "2")]
+    pub nat_type: i32,
+    #[prost(string, tag = "3")]
+    pub licence_key: String,
+    #[prost(int32, tag = "4")]
+    pub conn_type: i32,
+    #[prost(string, tag = "5")]
+    pub token: String,
+    #[prost(string, tag = "6")]
+    pub version: String,
+}
+
+/// 打洞请求失败的原因
+#[derive(Clone, Copy, Debug, PartialEq, Eq, prost::Enumeration)]
+#[repr(i32)]
+pub enum PunchHoleFailure {
+    IdNotExist = 0,
+    Offline = 2,
+    LicenseMismatch = 3,
+    LicenseOveruse = 4,
+}
+
+/// 打洞请求的响应
+#[derive(Clone, PartialEq, Message)]
+pub struct PunchHoleResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub socket_addr: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub pk: Vec<u8>,
+    #[prost(enumeration = "PunchHoleFailure", tag = "3")]
+    pub failure: i32,
+    #[prost(string, tag = "4")]
+    pub relay_server: String,
+    #[prost(string, tag = "7")]
+    pub other_failure: String,
+}
+
+/// 注册 ID 与机器 UUID、公钥的对应关系
+#[derive(Clone, PartialEq, Message)]
+pub struct RegisterPk {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub uuid: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub pk: Vec<u8>,
+    #[prost(string, tag = "4")]
+    pub old_id: String,
+}
+
+/// 注册结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq, prost::Enumeration)]
+#[repr(i32)]
+pub enum RegisterPkResult {
+    Ok = 0,
+    UuidMismatch = 2,
+    IdExists = 3,
+    TooFrequent = 4,
+    InvalidIdFormat = 5,
+    NotSupport = 6,
+    ServerError = 7,
+}
+
+/// 注册请求的响应
+#[derive(Clone, PartialEq, Message)]
+pub struct RegisterPkResponse {
+    #[prost(enumeration = "RegisterPkResult", tag = "1")]
+    pub result: i32,
+    #[prost(int32, tag = "2")]
+    pub keep_alive: i32,
+}
+
+/// 查询一组 ID 是否在线
+#[derive(Clone, PartialEq, Message)]
+pub struct OnlineRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, repeated, tag = "2")]
+    pub peers: Vec<String>,
+}
+
+/// 在线状态位图，第 i 个 ID 对应第 `i / 8` 字节从高位起的第 `i % 8` 位
+#[derive(Clone, PartialEq, Message)]
+pub struct OnlineResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub states: Vec<u8>,
+}
+
+/// 与 hbbs 交换的所有消息的外层包装
+#[derive(Clone, PartialEq, Message)]
+pub struct RendezvousMessage {
+    #[prost(oneof = "rendezvous_message::Union", tags = "8, 11, 15, 16, 23, 24")]
+    pub union: Option<rendezvous_message::Union>,
+}
+
+pub mod rendezvous_message {
+    /// 消息的具体类型，未列出的类型解码为 `None`
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Union {
+        #[prost(message, tag = "8")]
+        PunchHoleRequest(super::PunchHoleRequest),
+        #[prost(message, tag = "11")]
+        PunchHoleResponse(super::PunchHoleResponse),
+        #[prost(message, tag = "15")]
+        RegisterPk(super::RegisterPk),
+        #[prost(message, tag = "16")]
+        RegisterPkResponse(super::RegisterPkResponse),
+        #[prost(message, tag = "23")]
+        OnlineRequest(super::OnlineRequest),
+        #[prost(message, tag = "24")]
+        OnlineResponse(super::OnlineResponse),
+    }
+}
+
+impl RendezvousMessage {
+    /// 包装一条消息
+    pub fn new(union: rendezvous_message::Union) -> Self {
+        RendezvousMessage { union: Some(union) }
+    }
+}
+
+/// 为 TCP 消息加上 RustDesk 的长度头
+///
+/// 长度左移两位后以小端序写入 1 到 4 个字节，最低两位记录长度头的字节数减一。
+pub fn encode_frame(payload: &[u8]) -> Vec<u8> {
+    let len = payload.len() as u32;
+    let mut frame = Vec::with_capacity(payload.len() + 4);
+    if len <= 0x3F {
+        frame.push((len << 2) as u8);
+    } else if len <= 0x3FFF {
+        frame.extend_from_slice(&((len << 2) as u16 | 0x1).to_le_bytes());
+    } else if len <= 0x3F_FFFF {
+        frame.extend_from_slice(&((len << 2) | 0x2).to_le_bytes()[..3]);
+    } else {
+        frame.extend_from_slice(&((len << 2) | 0x3).to_le_bytes());
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// 从流中读取一帧
+pub fn read_frame(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut head = [0u8; 4];
+    reader.read_exact(&mut head[..1])?;
+    let head_len = (head[0] & 0x3) as usize + 1;
+    reader.read_exact(&mut head[1..head_len])?;
+    let len = (u32::from_le_bytes(head) >> 2) as usize;
+    if len > MAX_FRAME_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {} bytes", len),
+        ));
+    }
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+/// ID 在服务器上的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdStatus {
+    /// 未被注册，可以使用
+    Available,
+    /// 已被注册，但对应的机器当前离线
+    Offline,
+    /// 已被注册，且对应的机器当前在线
+    Online,
+}
+
+/// 为没有端口的服务器地址补上 hbbs 的默认端口
+pub fn with_default_port(address: &str) -> String {
+    let address = address.trim();
+    if address.starts_with('[') {
+        if address.contains("]:") {
+            address.to_string()
+        } else {
+            format!("{}:{}", address, RENDEZVOUS_PORT)
+        }
+    } else {
+        match address.matches(':').count() {
+            0 => format!("{}:{}", address, RENDEZVOUS_PORT),
+            1 => address.to_string(),
+            // 不带方括号的 IPv6 地址
+            _ => format!("[{}]:{}", address, RENDEZVOUS_PORT),
+        }
+    }
+}
+
+/// 查询 ID 在服务器上的状态
+///
+/// 先通过 TCP 发送在线查询；不在线时再通过 UDP 发送打洞请求，
+/// 根据服务器返回的失败原因区分“未注册”和“已注册但离线”。
+/// 对方离线时服务器不会转发打洞请求，因此查询不会打扰已部署的机器。
+pub fn check_id(
+    server: &str,
+    id: &str,
+    licence_key: &str,
+    timeout: Duration,
+) -> AppResult<IdStatus> {
+    let address = resolve(server)?;
+    if query_online(address, id, timeout).map_err(|e| network_error(server, e))? {
+        return Ok(IdStatus::Online);
+    }
+
+    let response =
+        punch_hole(address, id, licence_key, timeout).map_err(|e| network_error(server, e))?;
+    if !response.other_failure.is_empty() {
+        return Err(AppError::NetworkError(t!(
+            "rendezvous_error_rejected",
+            server = server,
+            reason = response.other_failure
+        )));
+    }
+    // 对方在两次查询之间上线时，服务器返回对方的地址或中继服务器
+    if !response.socket_addr.is_empty() || !response.relay_server.is_empty() {
+        return Ok(IdStatus::Online);
+    }
+    match PunchHoleFailure::try_from(response.failure) {
+        Ok(PunchHoleFailure::IdNotExist) => Ok(IdStatus::Available),
+        Ok(PunchHoleFailure::Offline) => Ok(IdStatus::Offline),
+        Ok(PunchHoleFailure::LicenseMismatch) => Err(AppError::NetworkError(t!(
+            "rendezvous_error_key_mismatch",
+            server = server
+        ))),
+        Ok(PunchHoleFailure::LicenseOveruse) => Err(AppError::NetworkError(t!(
+            "rendezvous_error_rejected",
+            server = server,
+            reason = "LICENSE_OVERUSE"
+        ))),
+        Err(_) => Err(AppError::NetworkError(t!(
+            "rendezvous_error_rejected",
+            server = server,
+            reason = response.failure
+        ))),
+    }
+}
+
+/// 在服务器上注册 ID 与机器 UUID、公钥的对应关系
+///
+/// 与 RustDesk 客户端启动后发送的注册请求相同。`uuid` 和 `pk` 必须与客户端之后发送的一致，
+/// 否则服务器会认为是另一台机器在使用该 ID。
+pub fn register_pk(
+    server: &str,
+    id: &str,
+    uuid: &[u8],
+    pk: &[u8],
+    timeout: Duration,
+) -> AppResult<()> {
+    let address = resolve(server)?;
+    let request = RendezvousMessage::new(rendezvous_message::Union::RegisterPk(RegisterPk {
+        id: id.to_string(),
+        uuid: uuid.to_vec(),
+        pk: pk.to_vec(),
+        old_id: String::new(),
+    }));
+    let response = udp_exchange(address, &request, timeout, |union| match union {
+        rendezvous_message::Union::RegisterPkResponse(response) => Some(response),
+        _ => None,
+    })
+    .map_err(|e| network_error(server, e))?;
+
+    let message = match RegisterPkResult::try_from(response.result) {
+        Ok(RegisterPkResult::Ok) => return Ok(()),
+        Ok(RegisterPkResult::UuidMismatch) => "register_error_uuid_mismatch",
+        Ok(RegisterPkResult::IdExists) => "register_error_id_exists",
+        Ok(RegisterPkResult::TooFrequent) => "register_error_too_frequent",
+        Ok(RegisterPkResult::InvalidIdFormat) => "register_error_invalid_id_format",
+        Ok(RegisterPkResult::NotSupport) => "register_error_not_support",
+        Ok(RegisterPkResult::ServerError) | Err(_) => "register_error_server_error",
+    };
+    Err(AppError::NetworkError(t!(
+        message,
+        id = id,
+        server = server
+    )))
+}
+
+/// 解析服务器地址，优先使用第一个地址
+fn resolve(server: &str) -> AppResult<SocketAddr> {
+    with_default_port(server)
+        .to_socket_addrs()
+        .map_err(|e| network_error(server, e))?
+        .next()
+        .ok_or_else(|| {
+            network_error(
+                server,
+                io::Error::new(io::ErrorKind::NotFound, t!("rendezvous_error_no_address")),
+            )
+        })
+}
+
+fn network_error(server: &str, error: io::Error) -> AppError {
+    match error.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            AppError::NetworkError(t!("rendezvous_error_timeout", server = server))
+        }
+        _ => AppError::NetworkError(t!(
+            "rendezvous_error_connect",
+            server = server,
+            error = error
+        )),
+    }
+}
+
+/// 通过 TCP 查询 ID 是否在线
+fn query_online(address: SocketAddr, id: &str, timeout: Duration) -> io::Result<bool> {
+    let mut stream = TcpStream::connect_timeout(&address, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let request = RendezvousMessage::new(rendezvous_message::Union::OnlineRequest(OnlineRequest {
+        id: String::new(),
+        peers: vec![id.to_string()],
+    }));
+    stream.write_all(&encode_frame(&request.encode_to_vec()))?;
+
+    // 忽略服务器可能先发送的其他消息，直到收到在线查询的响应
+    loop {
+        let frame = read_frame(&mut stream)?;
+        let message = RendezvousMessage::decode(frame.as_slice()).map_err(invalid_data)?;
+        if let Some(rendezvous_message::Union::OnlineResponse(response)) = message.union {
+            return Ok(response
+                .states
+                .first()
+                .is_some_and(|state| state & 0x80 != 0));
+        }
+    }
+}
+
+/// 通过 UDP 发送打洞请求并等待响应
+fn punch_hole(
+    address: SocketAddr,
+    id: &str,
+    licence_key: &str,
+    timeout: Duration,
+) -> io::Result<PunchHoleResponse> {
+    let request = RendezvousMessage::new(rendezvous_message::Union::PunchHoleRequest(
+        PunchHoleRequest {
+            id: id.to_string(),
+            licence_key: licence_key.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            ..PunchHoleRequest::default()
+        },
+    ));
+    udp_exchange(address, &request, timeout, |union| match union {
+        rendezvous_message::Union::PunchHoleResponse(response) => Some(response),
+        _ => None,
+    })
+}
+
+/// 通过 UDP 发送请求，直到 `accept` 接受收到的消息或超时
+///
+/// UDP 数据包可能丢失，因此每隔 `UDP_RESEND_INTERVAL` 重发一次请求；
+/// 查询和注册请求都可以安全地重复发送。
+fn udp_exchange<T>(
+    address: SocketAddr,
+    request: &RendezvousMessage,
+    timeout: Duration,
+    mut accept: impl FnMut(rendezvous_message::Union) -> Option<T>,
+) -> io::Result<T> {
+    let local: SocketAddr = if address.is_ipv4() {
+        ([0, 0, 0, 0], 0).into()
+    } else {
+        ([0u16; 8], 0).into()
+    };
+    let socket = UdpSocket::bind(local)?;
+    socket.connect(address)?;
+    let request = request.encode_to_vec();
+
+    let deadline = Instant::now() + timeout;
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        socket.send(&request)?;
+        let resend_at = Instant::now() + UDP_RESEND_INTERVAL;
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(io::ErrorKind::TimedOut.into());
+            }
+            if now >= resend_at {
+                break;
+            }
+            socket.set_read_timeout(Some(deadline.min(resend_at) - now))?;
+            let len = match socket.recv(&mut buf) {
+                Ok(len) => len,
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            // 忽略无法解码的数据包和其他类型的消息
+            if let Some(result) = RendezvousMessage::decode(&buf[..len])
+                .ok()
+                .and_then(|message| message.union)
+                .and_then(&mut accept)
+            {
+                return Ok(result);
+            }
+        }
+    }
+}
+
+fn invalid_data(error: prost::DecodeError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
@@ -0,0 +1,136 @@
+//! 口令加密模块
+//!
+//! 迁移包、批量处理结果等包含明文 ID、密码或机器 UUID 的输出可以用口令加密，
+//! 便于保存在版本库中或通过不可信的渠道传输。
+//!
+//! 加密结果为 JSON 文本：口令经 Argon2id 派生密钥，内容以 XChaCha20-Poly1305 加密，
+//! 文件头（内容类型、版本和派生参数）作为附加数据参与认证，修改任何字段都无法解密。
+
+use super::error::{AppError, AppResult};
+use super::secret::SecretString;
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use sodiumoxide::base64;
+use sodiumoxide::crypto::aead::xchacha20poly1305_ietf as aead;
+use sodiumoxide::crypto::pwhash::argon2id13;
+use zeroize::Zeroizing;
+
+/// 提供口令的环境变量
+pub const PASSPHRASE_ENV: &str = "CUSTOM_RUSTDESK_PASSPHRASE";
+
+/// 当前版本
+pub const SEALED_VERSION: u32 = 1;
+
+/// 密钥派生算法名称
+const KDF_ARGON2ID13: &str = "argon2id13";
+
+/// 文件头，序列化结果即为认证的附加数据
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+    kdf: String,
+    opslimit: u64,
+    memlimit: u64,
+    salt: String,
+}
+
+/// 完整的加密结果
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    #[serde(flatten)]
+    header: Header,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// 用口令加密内容，`format` 标识内容的类型，返回 JSON 文本
+pub fn seal(format: &str, plaintext: &[u8], passphrase: &SecretString) -> AppResult<String> {
+    let salt = argon2id13::gen_salt();
+    let opslimit = argon2id13::OPSLIMIT_INTERACTIVE;
+    let memlimit = argon2id13::MEMLIMIT_INTERACTIVE;
+    let header = Header {
+        format: format.to_string(),
+        version: SEALED_VERSION,
+        kdf: KDF_ARGON2ID13.to_string(),
+        opslimit: opslimit.0 as u64,
+        memlimit: memlimit.0 as u64,
+        salt: base64::encode(salt.0, base64::Variant::Original),
+    };
+    let additional_data =
+        serde_json::to_vec(&header).map_err(|e| AppError::CryptoError(e.to_string()))?;
+
+    let mut key = aead::Key([0; aead::KEYBYTES]);
+    derive_key(&mut key.0, passphrase, &salt, opslimit, memlimit)?;
+    let nonce = aead::gen_nonce();
+    let ciphertext = aead::seal(plaintext, Some(&additional_data), &nonce, &key);
+
+    let envelope = Envelope {
+        header,
+        nonce: base64::encode(nonce.0, base64::Variant::Original),
+        ciphertext: base64::encode(ciphertext, base64::Variant::Original),
+    };
+    serde_json::to_string_pretty(&envelope).map_err(|e| AppError::CryptoError(e.to_string()))
+}
+
+/// 用口令解密，返回内容类型和明文
+pub fn open(content: &str, passphrase: &SecretString) -> AppResult<(String, Zeroizing<Vec<u8>>)> {
+    let invalid = || AppError::ValidationError(t!("sealed_error_format"));
+    let envelope: Envelope = serde_json::from_str(content).map_err(|_| invalid())?;
+    let header = &envelope.header;
+    if header.kdf != KDF_ARGON2ID13 {
+        return Err(invalid());
+    }
+    if header.version != SEALED_VERSION {
+        return Err(AppError::ValidationError(t!(
+            "sealed_error_version",
+            version = header.version
+        )));
+    }
+
+    let decode = |value: &str| base64::decode(value, base64::Variant::Original).ok();
+    let salt = decode(&header.salt)
+        .and_then(|salt| argon2id13::Salt::from_slice(&salt))
+        .ok_or_else(invalid)?;
+    let nonce = decode(&envelope.nonce).ok_or_else(invalid)?;
+    let ciphertext = decode(&envelope.ciphertext).ok_or_else(invalid)?;
+    // 限制派生参数，避免损坏或伪造的文件耗尽内存
+    let opslimit = usize::try_from(header.opslimit)
+        .ok()
+        .filter(|opslimit| *opslimit <= argon2id13::OPSLIMIT_SENSITIVE.0)
+        .ok_or_else(invalid)?;
+    let memlimit = usize::try_from(header.memlimit)
+        .ok()
+        .filter(|memlimit| *memlimit <= argon2id13::MEMLIMIT_SENSITIVE.0)
+        .ok_or_else(invalid)?;
+    let opslimit = argon2id13::OpsLimit(opslimit);
+    let memlimit = argon2id13::MemLimit(memlimit);
+
+    let nonce = aead::Nonce::from_slice(&nonce).ok_or_else(invalid)?;
+    let additional_data =
+        serde_json::to_vec(header).map_err(|e| AppError::CryptoError(e.to_string()))?;
+    let mut key = aead::Key([0; aead::KEYBYTES]);
+    derive_key(&mut key.0, passphrase, &salt, opslimit, memlimit)?;
+    let plaintext = aead::open(&ciphertext, Some(&additional_data), &nonce, &key)
+        .map_err(|_| AppError::CryptoError(t!("sealed_error_passphrase")))?;
+    Ok((header.format.clone(), Zeroizing::new(plaintext)))
+}
+
+/// 由口令派生密钥
+fn derive_key(
+    key: &mut [u8],
+    passphrase: &SecretString,
+    salt: &argon2id13::Salt,
+    opslimit: argon2id13::OpsLimit,
+    memlimit: argon2id13::MemLimit,
+) -> AppResult<()> {
+    argon2id13::derive_key(
+        key,
+        passphrase.expose().as_bytes(),
+        salt,
+        opslimit,
+        memlimit,
+    )
+    .map_err(|_| AppError::CryptoError(t!("sealed_error_kdf")))?;
+    Ok(())
+}
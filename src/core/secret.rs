@@ -0,0 +1,109 @@
+//! 敏感数据模块
+//!
+//! 提供在释放时清零内存、并在 `Debug` 输出中隐藏内容的包装类型，
+//! 用于保存机器 UUID（加密密钥）和解密得到的明文。
+
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// 是否在输出中显示完整的 UUID
+static REVEAL_UUID: AtomicBool = AtomicBool::new(false);
+
+/// 释放时清零的敏感字符串
+#[derive(Clone, Default, PartialEq, Eq, Zeroize, ZeroizeOnDrop, Deserialize)]
+#[serde(from = "String")]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// 包装字符串，接管其内存
+    pub fn new(value: String) -> Self {
+        SecretString(value)
+    }
+
+    /// 取得明文内容，调用方不应复制或长期保存
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// 是否为空字符串
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 用于终端、JSON 和日志输出的形式，默认遮盖 UUID 的中间部分
+    pub fn display_uuid(&self) -> String {
+        if is_uuid_revealed() {
+            self.0.clone()
+        } else {
+            mask_uuid(&self.0)
+        }
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretString(<redacted>)")
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        SecretString::new(value)
+    }
+}
+
+impl FromStr for SecretString {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(SecretString::from(value))
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        SecretString::new(value.to_string())
+    }
+}
+
+/// 将明文字节转换为字符串，转换过程中的缓冲区同样会被清零
+pub fn secret_from_utf8(bytes: Zeroizing<Vec<u8>>) -> Result<SecretString, std::str::Utf8Error> {
+    let text = std::str::from_utf8(&bytes)?;
+    Ok(SecretString::new(text.to_string()))
+}
+
+/// 设置是否在输出中显示完整的 UUID
+pub fn set_reveal_uuid(reveal: bool) {
+    REVEAL_UUID.store(reveal, Ordering::Relaxed);
+}
+
+/// 输出中是否显示完整的 UUID
+pub fn is_uuid_revealed() -> bool {
+    REVEAL_UUID.load(Ordering::Relaxed)
+}
+
+/// 遮盖 UUID，只保留首尾各 4 个字符和连字符
+///
+/// 过短的输入全部遮盖，避免泄露大部分内容。
+pub fn mask_uuid(uuid: &str) -> String {
+    const VISIBLE: usize = 4;
+    let chars: Vec<char> = uuid.chars().collect();
+    let len = chars.len();
+
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            let visible = len > VISIBLE * 2 + VISIBLE && (i < VISIBLE || i >= len - VISIBLE);
+            if visible || c == '-' {
+                c
+            } else {
+                '*'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,113 @@
+//! UUID 密钥模块
+//!
+//! RustDesk 直接以 UUID 字符串的字节作为 32 字节的密钥：超出部分被截断，不足部分以 0 填充。
+//! Windows 和 macOS 报告 36 个字符的带连字符 UUID，只有前 32 字节参与加密，
+//! 最后 4 个字符不同的 UUID 得到相同的密钥；Linux 报告 32 个字符的 machine-id，全部参与加密。
+//! 同一个 UUID 的两种写法得到不同的密钥，必须使用目标机器报告的写法。
+
+use super::crypto::{effective_key, KEY_BYTES};
+use super::error::AppResult;
+use super::secret::SecretString;
+use super::validation::validate_uuid;
+use zeroize::Zeroizing;
+
+/// UUID 的写法
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UuidForm {
+    /// 带连字符的 36 字符形式，Windows 和 macOS 使用
+    Hyphenated,
+    /// 不带连字符的 32 字符形式，Linux 的 machine-id 使用
+    Raw,
+}
+
+impl UuidForm {
+    /// 判断 UUID 的写法，格式不正确时返回 `None`
+    pub fn detect(uuid: &str) -> Option<Self> {
+        validate_uuid(uuid).ok()?;
+        if uuid.contains('-') {
+            Some(UuidForm::Hyphenated)
+        } else {
+            Some(UuidForm::Raw)
+        }
+    }
+
+    /// 另一种写法
+    pub fn other(self) -> Self {
+        match self {
+            UuidForm::Hyphenated => UuidForm::Raw,
+            UuidForm::Raw => UuidForm::Hyphenated,
+        }
+    }
+
+    /// 命令行参数中使用的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            UuidForm::Hyphenated => "hyphenated",
+            UuidForm::Raw => "raw",
+        }
+    }
+}
+
+/// 将 UUID 转换为指定的写法，保留大小写
+pub fn convert_uuid(uuid: &SecretString, form: UuidForm) -> AppResult<SecretString> {
+    validate_uuid(uuid.expose())?;
+    let hex: Zeroizing<String> =
+        Zeroizing::new(uuid.expose().trim().chars().filter(|c| *c != '-').collect());
+    let converted = match form {
+        UuidForm::Raw => hex.to_string(),
+        UuidForm::Hyphenated => format!(
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        ),
+    };
+    Ok(SecretString::new(converted))
+}
+
+/// UUID 作为密钥的情况
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UuidKey {
+    /// UUID 的字节长度
+    pub length: usize,
+    /// 实际作为密钥的字节数
+    pub used: usize,
+}
+
+impl UuidKey {
+    /// 检查 UUID 作为密钥的情况
+    pub fn new(uuid: &str) -> Self {
+        UuidKey {
+            length: uuid.len(),
+            used: uuid.len().min(KEY_BYTES),
+        }
+    }
+
+    /// 被截断而不参与加密的字节数
+    pub fn truncated(&self) -> usize {
+        self.length - self.used
+    }
+
+    /// 以 0 填充的字节数
+    pub fn padding(&self) -> usize {
+        KEY_BYTES - self.used
+    }
+}
+
+/// 两个 UUID 是否得到相同的密钥
+pub fn same_key(first: &str, second: &str) -> bool {
+    effective_key(first) == effective_key(second)
+}
+
+/// 用于显示的密钥部分，遮盖方式与完整 UUID 相同
+pub fn display_key(uuid: &SecretString) -> String {
+    let used = UuidKey::new(uuid.expose()).used;
+    let count = uuid
+        .expose()
+        .char_indices()
+        .take_while(|(index, _)| *index < used)
+        .count();
+    uuid.display_uuid().chars().take(count).collect()
+}
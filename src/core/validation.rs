@@ -5,6 +5,8 @@
 use super::error::{AppError, AppResult};
 use regex::Regex;
 use rust_i18n::t;
+use sodiumoxide::base64;
+use std::net::Ipv6Addr;
 use std::sync::OnceLock;
 
 /// 标准 UUID 格式验证的正则表达式（带连字符）
@@ -119,6 +121,131 @@ impl Validator<str> for CustomIdValidator {
     }
 }
 
+/// 永久密码验证器
+struct PasswordValidator;
+
+impl Validator<str> for PasswordValidator {
+    fn validate(&self, password: &str) -> AppResult<()> {
+        if password.is_empty() {
+            return Err(AppError::ValidationError(t!("validation_error_password_empty")));
+        }
+
+        // RustDesk 只加密不超过 128 个字符的密码，更长的密码会以明文保存
+        if password.chars().count() > 128 {
+            return Err(AppError::ValidationError(t!("validation_error_password_length")));
+        }
+
+        if password.chars().any(char::is_control) {
+            return Err(AppError::ValidationError(t!(
+                "validation_error_password_control_chars"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// 服务器地址验证器，接受 `host` 或 `host:port`，IPv6 地址写作 `[addr]:port`
+struct ServerAddressValidator;
+
+impl ServerAddressValidator {
+    /// 拆分主机和端口
+    fn split(address: &str) -> (&str, Option<&str>) {
+        if let Some((host, rest)) = address.strip_prefix('[').and_then(|r| r.split_once(']')) {
+            let port = (!rest.is_empty()).then(|| rest.strip_prefix(':').unwrap_or(rest));
+            return (host, port);
+        }
+        // 没有方括号的 IPv6 地址不能带端口
+        if address.matches(':').count() > 1 {
+            return (address, None);
+        }
+        match address.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (address, None),
+        }
+    }
+
+    /// 主机名或 IP 地址是否有效
+    fn is_valid_host(host: &str) -> bool {
+        if host.parse::<Ipv6Addr>().is_ok() {
+            return true;
+        }
+        host.len() <= 253
+            && host.split('.').all(|label| {
+                (1..=63).contains(&label.len())
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+                    && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            })
+    }
+}
+
+impl Validator<str> for ServerAddressValidator {
+    fn validate(&self, address: &str) -> AppResult<()> {
+        let address = address.trim();
+        if address.is_empty() {
+            return Err(AppError::ValidationError(t!("validation_error_server_empty")));
+        }
+
+        let (host, port) = Self::split(address);
+        if !Self::is_valid_host(host) {
+            return Err(AppError::ValidationError(t!(
+                "validation_error_server_host",
+                address = address
+            )));
+        }
+
+        if let Some(port) = port {
+            if !matches!(port.parse::<u16>(), Ok(port) if port != 0) {
+                return Err(AppError::ValidationError(t!(
+                    "validation_error_server_port",
+                    address = address
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// API 服务器验证器，要求 `http://` 或 `https://` 开头的地址
+struct ApiServerValidator;
+
+impl Validator<str> for ApiServerValidator {
+    fn validate(&self, url: &str) -> AppResult<()> {
+        let url = url.trim();
+        if url.is_empty() {
+            return Err(AppError::ValidationError(t!("validation_error_server_empty")));
+        }
+
+        let Some(rest) = url
+            .strip_prefix("http://")
+            .or_else(|| url.strip_prefix("https://"))
+        else {
+            return Err(AppError::ValidationError(t!("validation_error_api_server_scheme")));
+        };
+        let address = rest.split('/').next().unwrap_or_default();
+        ServerAddressValidator.validate(address)
+    }
+}
+
+/// 服务器公钥验证器：RustDesk 服务器的公钥为 32 字节的 Ed25519 公钥的 base64 编码
+struct PublicKeyValidator;
+
+impl Validator<str> for PublicKeyValidator {
+    fn validate(&self, key: &str) -> AppResult<()> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(AppError::ValidationError(t!("validation_error_public_key_empty")));
+        }
+
+        match base64::decode(key, base64::Variant::Original) {
+            Ok(bytes) if bytes.len() == 32 => Ok(()),
+            _ => Err(AppError::ValidationError(t!("validation_error_public_key_format"))),
+        }
+    }
+}
+
 /// 验证 UUID 格式
 pub fn validate_uuid(uuid: &str) -> AppResult<()> {
     UuidValidator.validate(uuid)
@@ -133,3 +260,49 @@ pub fn validate_custom_id(custom_id: &str) -> AppResult<()> {
 pub fn validate_encrypted_id(encrypted_id: &str) -> AppResult<()> {
     EncryptedIdValidator.validate(encrypted_id)
 }
+
+/// 验证永久密码
+pub fn validate_password(password: &str) -> AppResult<()> {
+    PasswordValidator.validate(password)
+}
+
+/// 验证 ID 服务器或中继服务器地址
+pub fn validate_server_address(address: &str) -> AppResult<()> {
+    ServerAddressValidator.validate(address)
+}
+
+/// 验证 API 服务器地址
+pub fn validate_api_server(url: &str) -> AppResult<()> {
+    ApiServerValidator.validate(url)
+}
+
+/// 验证服务器公钥
+pub fn validate_public_key(key: &str) -> AppResult<()> {
+    PublicKeyValidator.validate(key)
+}
+
+/// 检查 ID 是否符合 RustDesk 对 ID 的要求，返回所有警告
+///
+/// RustDesk 要求 ID 以字母开头，长度为 6 到 16 个字符，只包含字母、数字、`-` 和 `_`。
+/// 不符合要求的 ID 仍可加密，但可能被 ID 服务器拒绝。
+pub fn custom_id_warnings(custom_id: &str) -> Vec<String> {
+    if let Err(e) = validate_custom_id(custom_id) {
+        return vec![e.to_string()];
+    }
+
+    let mut warnings = Vec::new();
+    if custom_id.trim() != custom_id {
+        warnings.push(t!("id_warning_whitespace"));
+    }
+    let id = custom_id.trim();
+    if !(6..=16).contains(&id.chars().count()) {
+        warnings.push(t!("id_warning_length"));
+    }
+    if !id.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        warnings.push(t!("id_warning_first_char"));
+    }
+    if !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        warnings.push(t!("id_warning_chars"));
+    }
+    warnings
+}
@@ -1,11 +1,11 @@
 #[macro_use]
 extern crate rust_i18n;
 
-i18n!("i18n");
+i18n!("i18n", fallback = "en");
 
 mod app;
 mod core;
 
-fn main() {
-    app::cli::run();
+fn main() -> std::process::ExitCode {
+    app::cli::run()
 }
@@ -0,0 +1,1643 @@
+//! 命令行端到端集成测试
+//!
+//! 直接运行编译好的可执行文件，通过脚本化的标准输入驱动交互菜单和 UUID 确认提示，
+//! 并检查标准输出、退出码以及生成的文件。剪切板通过环境变量重定向到临时文件，
+//! 因此测试可以在无图形界面的 Linux 环境中运行。
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const UUID: &str = "12345678-1234-1234-1234-123456789012";
+const CUSTOM_ID: &str = "TestComputer";
+const ENC_ID: &str = "00Nhyr/aazYBq3jkSuNwA6trMEV2xPMoziEAJWog==";
+const SERVER_KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+
+/// 一次命令行调用的结果
+struct Run {
+    output: Output,
+    clipboard: Option<String>,
+}
+
+impl Run {
+    fn stdout(&self) -> String {
+        String::from_utf8_lossy(&self.output.stdout).into_owned()
+    }
+
+    fn stderr(&self) -> String {
+        String::from_utf8_lossy(&self.output.stderr).into_owned()
+    }
+}
+
+/// 为每个测试创建独立的临时目录，避免并行测试互相覆盖剪切板文件
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "custom-rustdesk-cli-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// 运行可执行文件，写入给定的标准输入并收集输出
+fn run(name: &str, args: &[&str], stdin: &str) -> Run {
+    run_with_env(name, args, stdin, &[])
+}
+
+/// 与 `run` 相同，但额外设置环境变量
+///
+/// 默认清除语言和口令相关的环境变量，使输出不受运行测试的环境影响。
+fn run_with_env(name: &str, args: &[&str], stdin: &str, envs: &[(&str, &str)]) -> Run {
+    let dir = scratch_dir(name);
+    let clipboard_file = dir.join("clipboard.txt");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_custom-rustdesk"))
+        .args(args)
+        .env("CUSTOM_RUSTDESK_CLIPBOARD_FILE", &clipboard_file)
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env_remove("CUSTOM_RUSTDESK_PASSPHRASE")
+        .env("LANG", "C")
+        .envs(envs.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn custom-rustdesk");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let clipboard = read_optional(&clipboard_file);
+    let _ = fs::remove_dir_all(&dir);
+
+    Run { output, clipboard }
+}
+
+fn read_optional(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok()
+}
+
+/// 本机能否自动检测到 UUID；检测不到时跳过依赖确认提示的测试
+fn machine_uuid() -> Option<String> {
+    machine_uid::get().ok()
+}
+
+#[test]
+fn encrypt_with_explicit_uuid() {
+    let run = run("encrypt", &["encrypt", CUSTOM_ID, "--uuid", UUID], "");
+
+    assert!(run.output.status.success());
+    assert!(run
+        .stdout()
+        .contains(&format!("\"{}\" is encrypted to \"{}\"", CUSTOM_ID, ENC_ID)));
+    assert_eq!(run.clipboard.as_deref(), Some(ENC_ID));
+}
+
+#[test]
+fn decrypt_with_explicit_uuid() {
+    let run = run("decrypt", &["decrypt", ENC_ID, "--uuid", UUID], "");
+
+    assert!(run.output.status.success());
+    assert!(run
+        .stdout()
+        .contains(&format!("\"{}\" is decrypted to \"{}\"", ENC_ID, CUSTOM_ID)));
+    assert_eq!(run.clipboard.as_deref(), Some(CUSTOM_ID));
+}
+
+#[test]
+fn decrypt_with_wrong_uuid_fails() {
+    let other = "87654321-4321-4321-4321-210987654321";
+    let run = run("decrypt-wrong", &["decrypt", ENC_ID, "--uuid", other], "");
+
+    assert!(!run.output.status.success());
+    assert!(run.stdout().contains("Error occurred during decryption"));
+    assert_eq!(run.clipboard, None);
+}
+
+#[test]
+fn invalid_uuid_is_rejected() {
+    let run = run(
+        "invalid-uuid",
+        &["encrypt", CUSTOM_ID, "--uuid", "not-a-uuid"],
+        "",
+    );
+
+    assert!(!run.output.status.success());
+    assert!(run.stdout().contains("Invalid UUID format"));
+    assert_eq!(run.clipboard, None);
+}
+
+#[test]
+fn legacy_flags_still_work_with_warning() {
+    let encrypted = run("legacy-id", &["--id", CUSTOM_ID, "--uuid", UUID], "");
+
+    assert!(encrypted.output.status.success());
+    assert!(encrypted.stderr().contains("deprecated"));
+    assert_eq!(encrypted.clipboard.as_deref(), Some(ENC_ID));
+
+    let decrypted = run("legacy-eid", &["--eid", ENC_ID, "--uuid", UUID], "");
+
+    assert!(decrypted.output.status.success());
+    assert_eq!(decrypted.clipboard.as_deref(), Some(CUSTOM_ID));
+}
+
+#[test]
+fn conflicting_legacy_flags_are_rejected() {
+    let run = run(
+        "legacy-conflict",
+        &["--id", CUSTOM_ID, "--eid", ENC_ID, "--uuid", UUID],
+        "",
+    );
+
+    assert_eq!(run.output.status.code(), Some(2));
+    assert!(run.stderr().contains("cannot be used with"));
+    assert_eq!(run.clipboard, None);
+}
+
+#[test]
+fn password_round_trip() {
+    let encrypt_run = run(
+        "password-encrypt",
+        &["password", "encrypt", "S3cret!pass", "--uuid", UUID],
+        "",
+    );
+    assert!(encrypt_run.output.status.success());
+    let encrypted = encrypt_run.clipboard.expect("encrypted password copied");
+    assert!(encrypted.starts_with("00"));
+
+    let decrypt_run = run(
+        "password-decrypt",
+        &["password", "decrypt", &encrypted, "--uuid", UUID],
+        "",
+    );
+    assert!(decrypt_run.output.status.success());
+    assert_eq!(decrypt_run.clipboard.as_deref(), Some("S3cret!pass"));
+}
+
+#[test]
+fn apply_writes_enc_id_with_backup() {
+    let dir = scratch_dir("apply");
+    let config = dir.join("RustDesk.toml");
+    fs::write(&config, "enc_id = '00old'\nkey_confirmed = true\n").unwrap();
+
+    let applied = run(
+        "apply-run",
+        &[
+            "apply",
+            "--id",
+            CUSTOM_ID,
+            "--uuid",
+            UUID,
+            "--config",
+            config.to_str().unwrap(),
+        ],
+        "",
+    );
+
+    assert!(applied.output.status.success());
+    let stdout = applied.stdout();
+    assert!(stdout.contains("- enc_id = '00old'"));
+    assert!(stdout.contains(&format!("+ enc_id = \"{}\"", ENC_ID)));
+    let content = fs::read_to_string(&config).unwrap();
+    assert!(content.contains(ENC_ID));
+    assert!(content.contains("key_confirmed = true"));
+    let backups: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().contains(".bak."))
+        .collect();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(
+        fs::read_to_string(backups[0].path()).unwrap(),
+        "enc_id = '00old'\nkey_confirmed = true\n"
+    );
+
+    // 再次应用相同的 ID 时不做修改
+    let again = run(
+        "apply-again",
+        &[
+            "apply",
+            "--id",
+            CUSTOM_ID,
+            "--uuid",
+            UUID,
+            "--config",
+            config.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert!(again.output.status.success());
+    assert!(again.stdout().contains("Already up to date"));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn rekey_reencrypts_with_new_uuid() {
+    const NEW_UUID: &str = "87654321-4321-4321-4321-210987654321";
+    let dir = scratch_dir("rekey");
+    let config = dir.join("RustDesk.toml");
+    let original = format!("enc_id = '{}'\nkey_confirmed = true\n", ENC_ID);
+    fs::write(&config, &original).unwrap();
+
+    let rekeyed = run(
+        "rekey-run",
+        &[
+            "rekey",
+            "--from-uuid",
+            UUID,
+            "--to-uuid",
+            NEW_UUID,
+            "--config",
+            config.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert!(rekeyed.output.status.success());
+    assert!(rekeyed.stdout().contains("enc_id re-encrypted with the new UUID"));
+    let content = fs::read_to_string(&config).unwrap();
+    assert!(!content.contains(ENC_ID));
+    assert!(content.contains("key_confirmed = true"));
+    let backups: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().contains(".bak."))
+        .collect();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(fs::read_to_string(backups[0].path()).unwrap(), original);
+
+    let enc_id = content.split('"').nth(1).unwrap();
+    let decrypted = run(
+        "rekey-decrypt",
+        &["decrypt", enc_id, "--uuid", NEW_UUID, "--no-clipboard"],
+        "",
+    );
+    assert!(decrypted.stdout().contains(CUSTOM_ID));
+
+    // 旧 UUID 和新 UUID 都无法解密时不写入
+    let mismatch = run(
+        "rekey-mismatch",
+        &[
+            "rekey",
+            "--from-uuid",
+            "11111111-1111-1111-1111-111111111111",
+            "--to-uuid",
+            UUID,
+            "--config",
+            config.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert!(!mismatch.output.status.success());
+    assert!(mismatch.stdout().contains("nothing was written"));
+    assert_eq!(fs::read_to_string(&config).unwrap(), content);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn export_and_import_identity() {
+    const NEW_UUID: &str = "87654321-4321-4321-4321-210987654321";
+    let dir = scratch_dir("bundle");
+    let old_config = dir.join("old.toml");
+    let new_config = dir.join("new.toml");
+    let bundle = dir.join("identity.bundle");
+    fs::write(&old_config, format!("enc_id = '{}'\n", ENC_ID)).unwrap();
+    fs::write(&new_config, "enc_id = '00other'\n").unwrap();
+    let passphrase = [("CUSTOM_RUSTDESK_PASSPHRASE", "correct horse")];
+
+    let exported = run_with_env(
+        "bundle-export-missing",
+        &[
+            "export",
+            bundle.to_str().unwrap(),
+            "--uuid",
+            UUID,
+            "--config",
+            old_config.to_str().unwrap(),
+            "--server-config",
+            dir.join("missing.toml").to_str().unwrap(),
+        ],
+        "",
+        &passphrase,
+    );
+    assert!(!exported.output.status.success());
+    assert!(!bundle.exists());
+
+    let exported = run_with_env(
+        "bundle-export",
+        &[
+            "export",
+            bundle.to_str().unwrap(),
+            "--uuid",
+            UUID,
+            "--config",
+            old_config.to_str().unwrap(),
+        ],
+        "",
+        &passphrase,
+    );
+    assert!(exported.output.status.success());
+    assert!(exported.stdout().contains("ID: TestComputer"));
+    assert!(!fs::read_to_string(&bundle).unwrap().contains(CUSTOM_ID));
+
+    let wrong = run_with_env(
+        "bundle-import-wrong",
+        &[
+            "import",
+            bundle.to_str().unwrap(),
+            "--uuid",
+            NEW_UUID,
+            "--config",
+            new_config.to_str().unwrap(),
+        ],
+        "",
+        &[("CUSTOM_RUSTDESK_PASSPHRASE", "wrong")],
+    );
+    assert!(!wrong.output.status.success());
+    assert!(wrong.stdout().contains("Wrong passphrase"));
+    assert_eq!(fs::read_to_string(&new_config).unwrap(), "enc_id = '00other'\n");
+
+    let imported = run_with_env(
+        "bundle-import",
+        &[
+            "import",
+            bundle.to_str().unwrap(),
+            "--uuid",
+            NEW_UUID,
+            "--config",
+            new_config.to_str().unwrap(),
+        ],
+        "",
+        &passphrase,
+    );
+    assert!(imported.output.status.success());
+    let content = fs::read_to_string(&new_config).unwrap();
+    let enc_id = content.split('"').nth(1).unwrap();
+    let decrypted = run(
+        "bundle-decrypt",
+        &["decrypt", enc_id, "--uuid", NEW_UUID, "--no-clipboard"],
+        "",
+    );
+    assert!(decrypted.stdout().contains(CUSTOM_ID));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn passphrase_is_used_verbatim() {
+    let dir = scratch_dir("passphrase-verbatim");
+    let config = dir.join("RustDesk.toml");
+    let bundle = dir.join("identity.bundle");
+    fs::write(&config, format!("enc_id = '{}'\n", ENC_ID)).unwrap();
+
+    let exported = run(
+        "passphrase-verbatim-prompt",
+        &[
+            "export",
+            bundle.to_str().unwrap(),
+            "--uuid",
+            UUID,
+            "--config",
+            config.to_str().unwrap(),
+        ],
+        " correct horse \n correct horse \n",
+    );
+    assert!(exported.output.status.success());
+
+    let args = [
+        "import",
+        bundle.to_str().unwrap(),
+        "--uuid",
+        UUID,
+        "--config",
+        config.to_str().unwrap(),
+        "--dry-run",
+    ];
+    let trimmed = run_with_env(
+        "passphrase-verbatim-trimmed",
+        &args,
+        "",
+        &[("CUSTOM_RUSTDESK_PASSPHRASE", "correct horse")],
+    );
+    assert!(!trimmed.output.status.success());
+    assert!(trimmed.stdout().contains("Wrong passphrase"));
+    let verbatim = run_with_env(
+        "passphrase-verbatim-env",
+        &args,
+        "",
+        &[("CUSTOM_RUSTDESK_PASSPHRASE", " correct horse ")],
+    );
+    assert!(verbatim.output.status.success());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn apply_dry_run_leaves_file_untouched() {
+    let dir = scratch_dir("apply-dry");
+    let config = dir.join("RustDesk.toml");
+    fs::write(&config, "enc_id = '00old'\n").unwrap();
+
+    let run = run(
+        "apply-dry-run",
+        &[
+            "apply",
+            "--id",
+            CUSTOM_ID,
+            "--uuid",
+            UUID,
+            "--config",
+            config.to_str().unwrap(),
+            "--dry-run",
+        ],
+        "",
+    );
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains("Dry run"));
+    assert_eq!(fs::read_to_string(&config).unwrap(), "enc_id = '00old'\n");
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn ensure_is_idempotent() {
+    let dir = scratch_dir("ensure");
+    let config = dir.join("RustDesk.toml");
+    fs::write(&config, "enc_id = '00old'\nsalt = 'abc'\n").unwrap();
+    let config_arg = config.to_str().unwrap();
+    let ensure = |name: &str, extra: &[&str]| {
+        let mut args = vec!["ensure", "--id", CUSTOM_ID, "--uuid", UUID, "--config", config_arg];
+        args.extend_from_slice(extra);
+        let run = run(name, &args, "");
+        let report: serde_json::Value = serde_json::from_str(&run.stdout()).expect("valid JSON");
+        (run.output.status.success(), report)
+    };
+
+    // 预演模式只报告将要修改
+    let (success, report) = ensure("ensure-dry-run", &["--dry-run"]);
+    assert!(success);
+    assert_eq!(report["changed"], true);
+    assert_eq!(report["configs"][0]["status"], "changed");
+    assert_eq!(
+        fs::read_to_string(&config).unwrap(),
+        "enc_id = '00old'\nsalt = 'abc'\n"
+    );
+
+    let (success, report) = ensure("ensure-first", &[]);
+    assert!(success);
+    assert_eq!(report["changed"], true);
+    assert!(report["configs"][0]["backup"].is_string());
+    let written = fs::read_to_string(&config).unwrap();
+    assert!(written.contains(ENC_ID));
+    assert!(written.contains("salt = 'abc'"));
+
+    // 再次调用时不修改文件，也不产生新的备份
+    let (success, report) = ensure("ensure-second", &[]);
+    assert!(success);
+    assert_eq!(report["changed"], false);
+    assert_eq!(report["configs"][0]["status"], "unchanged");
+    assert_eq!(report["configs"][0]["previous_id"], CUSTOM_ID);
+    assert_eq!(fs::read_to_string(&config).unwrap(), written);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn ensure_reports_errors_as_json() {
+    let dir = scratch_dir("ensure-errors");
+    let missing = dir.join("missing.toml");
+
+    let missing_run = run(
+        "ensure-missing",
+        &[
+            "ensure",
+            "--id",
+            CUSTOM_ID,
+            "--uuid",
+            UUID,
+            "--config",
+            missing.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert!(!missing_run.output.status.success());
+    let report: serde_json::Value =
+        serde_json::from_str(&missing_run.stdout()).expect("valid JSON");
+    assert_eq!(report["changed"], false);
+    assert_eq!(report["configs"][0]["status"], "failed");
+    assert!(report["configs"][0]["error"].is_string());
+
+    let invalid_run = run(
+        "ensure-invalid-uuid",
+        &["ensure", "--id", CUSTOM_ID, "--uuid", "not-a-uuid"],
+        "",
+    );
+    assert!(!invalid_run.output.status.success());
+    let report: serde_json::Value =
+        serde_json::from_str(&invalid_run.stdout()).expect("valid JSON");
+    assert!(report["error"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid UUID format"));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn inspect_decrypts_config() {
+    let dir = scratch_dir("inspect");
+    let config = dir.join("RustDesk.toml");
+    fs::write(&config, format!("enc_id = '{}'\n", ENC_ID)).unwrap();
+
+    let run = run(
+        "inspect-run",
+        &["inspect", "--uuid", UUID, "--config", config.to_str().unwrap()],
+        "",
+    );
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains(&format!("ID: {}", CUSTOM_ID)));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn inspect_json_reports_id_password_and_key() {
+    let dir = scratch_dir("inspect-json");
+    let good = dir.join("good.toml");
+    let bad = dir.join("bad.toml");
+
+    let encrypted = run(
+        "inspect-json-password",
+        &["password", "encrypt", "S3cret!pass", "--uuid", UUID],
+        "",
+    )
+    .clipboard
+    .expect("encrypted password copied");
+    fs::write(
+        &good,
+        format!("enc_id = '{}'\npassword = '{}'\n", ENC_ID, encrypted),
+    )
+    .unwrap();
+    fs::write(&bad, format!("enc_id = '{}'\npassword = '00garbage'\n", ENC_ID)).unwrap();
+
+    let run = run(
+        "inspect-json-run",
+        &[
+            "inspect",
+            "--json",
+            "--uuid",
+            UUID,
+            "--config",
+            good.to_str().unwrap(),
+            "--config",
+            bad.to_str().unwrap(),
+        ],
+        "",
+    );
+
+    assert!(run.output.status.success());
+    let report: serde_json::Value = serde_json::from_str(&run.stdout()).expect("valid JSON");
+    assert_eq!(report["key"]["length"], 36);
+    assert_eq!(report["key"]["truncated"], true);
+    assert_eq!(report["auto_detected"], false);
+    assert_eq!(report["configs"][0]["id"], CUSTOM_ID);
+    assert_eq!(report["configs"][0]["password"], "decrypts");
+    assert_eq!(report["configs"][0]["id_warnings"], serde_json::json!([]));
+    assert_eq!(report["configs"][1]["password"], "undecryptable");
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn inspect_warns_about_unusual_id() {
+    let dir = scratch_dir("inspect-warn");
+    let config = dir.join("RustDesk.toml");
+    let enc_id = run("inspect-warn-encrypt", &["encrypt", "1ab", "--uuid", UUID], "")
+        .clipboard
+        .expect("encrypted ID copied");
+    fs::write(&config, format!("enc_id = '{}'\n", enc_id)).unwrap();
+
+    let run = run(
+        "inspect-warn-run",
+        &["inspect", "--uuid", UUID, "--config", config.to_str().unwrap()],
+        "",
+    );
+
+    assert!(run.output.status.success());
+    let stdout = run.stdout();
+    assert!(stdout.contains("ID: 1ab"));
+    assert!(stdout.contains("must start with a letter"));
+    assert!(stdout.contains("6 to 16 characters"));
+    assert!(stdout.contains("Password: not set"));
+    assert!(stdout.contains("truncated form"));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn server_set_writes_options_with_backup() {
+    let dir = scratch_dir("server-set");
+    let config = dir.join("RustDesk2.toml");
+    let original = "rendezvous_server = 'rs-ny.rustdesk.com:21116'\nnat_type = 1\n\n[options]\nlocal-ip-addr = '10.0.0.5'\n";
+    fs::write(&config, original).unwrap();
+
+    let set = run(
+        "server-set-run",
+        &[
+            "server",
+            "set",
+            "--id-server",
+            "rd.example.com",
+            "--relay-server",
+            "rd.example.com:21117",
+            "--api-server",
+            "https://rd.example.com:21114",
+            "--key",
+            SERVER_KEY,
+            "--config",
+            config.to_str().unwrap(),
+        ],
+        "",
+    );
+
+    assert!(set.output.status.success());
+    assert!(set
+        .stdout()
+        .contains("+ custom-rendezvous-server = \"rd.example.com\""));
+    let content = fs::read_to_string(&config).unwrap();
+    assert!(content.contains("local-ip-addr = '10.0.0.5'"));
+    assert!(content.contains("relay-server = \"rd.example.com:21117\""));
+    assert!(content.contains("api-server = \"https://rd.example.com:21114\""));
+    assert!(content.contains(&format!("key = \"{}\"", SERVER_KEY)));
+    let backups = fs::read_dir(&dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().contains(".bak."))
+        .count();
+    assert_eq!(backups, 1);
+
+    let show = run(
+        "server-show-run",
+        &["server", "show", "--config", config.to_str().unwrap()],
+        "",
+    );
+    assert!(show.output.status.success());
+    assert!(show
+        .stdout()
+        .contains("custom-rendezvous-server = rd.example.com"));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn server_set_rejects_invalid_options() {
+    let dir = scratch_dir("server-invalid");
+    let config = dir.join("RustDesk2.toml");
+    fs::write(&config, "[options]\n").unwrap();
+
+    for (flag, value) in [
+        ("--id-server", "rd.example.com:99999"),
+        ("--relay-server", "bad host"),
+        ("--api-server", "rd.example.com:21114"),
+        ("--key", "AAECAwQF"),
+    ] {
+        let run = run(
+            "server-invalid-run",
+            &["server", "set", flag, value, "--config", config.to_str().unwrap()],
+            "",
+        );
+        assert!(!run.output.status.success(), "{} {}", flag, value);
+        assert!(run.stdout().contains("Validation Error"));
+    }
+    assert_eq!(fs::read_to_string(&config).unwrap(), "[options]\n");
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn server_config_string_round_trip() {
+    let dir = scratch_dir("server-encode");
+    let config = dir.join("RustDesk2.toml");
+    fs::write(
+        &config,
+        format!(
+            "[options]\ncustom-rendezvous-server = 'rd.example.com'\nrelay-server = ''\nkey = '{}'\n",
+            SERVER_KEY
+        ),
+    )
+    .unwrap();
+
+    let encode = run(
+        "server-encode-run",
+        &["server", "encode", "--config", config.to_str().unwrap()],
+        "",
+    );
+    assert!(encode.output.status.success());
+    let config_string = encode.clipboard.clone().expect("config string copied");
+    assert!(encode.stdout().contains(&config_string));
+
+    let decode = run(
+        "server-decode-run",
+        &["server", "decode", &format!("rustdesk-licensed-{}.exe", config_string)],
+        "",
+    );
+    assert!(decode.output.status.success());
+    let stdout = decode.stdout();
+    assert!(stdout.contains("custom-rendezvous-server = rd.example.com"));
+    assert!(stdout.contains("relay-server = (not set)"));
+    assert!(stdout.contains(&format!("key = {}", SERVER_KEY)));
+
+    let invalid = run("server-decode-invalid", &["server", "decode", "garbage"], "");
+    assert!(!invalid.output.status.success());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn script_matches_golden_files() {
+    for (format, golden) in [
+        ("powershell", include_str!("golden/deploy.ps1")),
+        ("sh", include_str!("golden/deploy.sh")),
+    ] {
+        let run = run(
+            "script-golden",
+            &["script", "--id", CUSTOM_ID, "--uuid", UUID, "--format", format],
+            "",
+        );
+        assert!(run.output.status.success());
+        assert_eq!(run.stdout(), golden, "{} script differs from golden file", format);
+        assert!(golden.contains(ENC_ID));
+    }
+}
+
+#[test]
+fn script_is_written_to_file() {
+    let dir = scratch_dir("script-output");
+    let path = dir.join("deploy.sh");
+
+    let args = [
+        "script",
+        "--id",
+        "it's-me",
+        "--uuid",
+        UUID,
+        "--format",
+        "sh",
+        "--output",
+        path.to_str().unwrap(),
+    ];
+    let written = run("script-output-run", &args, "");
+
+    assert!(written.output.status.success());
+    assert!(written.stdout().contains("deploy.sh"));
+    let script = fs::read_to_string(&path).unwrap();
+    assert!(script.contains("CUSTOM_ID='it'\\''s-me'"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        let syntax = Command::new("sh").arg("-n").arg(&path).status().unwrap();
+        assert!(syntax.success());
+    }
+
+    // 不覆盖已有的文件
+    let again = run("script-output-exists", &args, "");
+    assert!(!again.output.status.success());
+    assert!(again.stdout().contains("already exists"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), script);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+/// 以 root 运行时 $HOME 与固定的 root 目录相同，配置文件只能修改一次，且不覆盖已有的备份
+#[cfg(target_os = "linux")]
+#[test]
+fn sh_script_patches_duplicate_directory_once() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = scratch_dir("script-duplicate-dir");
+    let config_dir = dir.join(".config/rustdesk");
+    fs::create_dir_all(&config_dir).unwrap();
+    let config = config_dir.join("RustDesk.toml");
+    let original = "enc_id = '00original'\nsalt = 'abc'\n";
+    fs::write(&config, original).unwrap();
+
+    // 将 root 的目录指向 $HOME，模拟以 root 运行
+    let script = include_str!("golden/deploy.sh")
+        .replace("/root/.config/rustdesk", "$HOME/.config/rustdesk");
+    let script_path = dir.join("deploy.sh");
+    fs::write(&script_path, script).unwrap();
+    // 替换 systemctl，避免操作运行测试的机器上的服务
+    let bin = dir.join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let systemctl = bin.join("systemctl");
+    fs::write(&systemctl, "#!/bin/sh\nexit 0\n").unwrap();
+    fs::set_permissions(&systemctl, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    // 连续运行两次，通常落在同一秒内
+    for _ in 0..2 {
+        let output = Command::new("sh")
+            .arg(&script_path)
+            .env("HOME", &dir)
+            .env("PATH", &path)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.matches("Updated").count(), 1);
+    }
+
+    let content = fs::read_to_string(&config).unwrap();
+    assert!(content.contains(ENC_ID));
+    assert!(content.contains("salt = 'abc'"));
+    let backups: Vec<String> = fs::read_dir(&config_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path != &config)
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect();
+    assert_eq!(backups.len(), 2);
+    assert!(backups.iter().any(|backup| backup == original));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn script_rejects_invalid_uuid() {
+    let run = run(
+        "script-invalid",
+        &["script", "--id", CUSTOM_ID, "--uuid", "not-a-uuid"],
+        "",
+    );
+    assert!(!run.output.status.success());
+    assert!(run.stdout().contains("Invalid UUID format"));
+}
+
+#[test]
+fn check_id_reports_missing_or_unreachable_server() {
+    let dir = scratch_dir("check-id");
+    let config = dir.join("RustDesk2.toml");
+    fs::write(&config, "[options]\n").unwrap();
+
+    let missing_run = run(
+        "check-id-missing",
+        &["check-id", CUSTOM_ID, "--config", config.to_str().unwrap()],
+        "",
+    );
+    assert!(!missing_run.output.status.success());
+    assert!(missing_run.stdout().contains("The ID server is required"));
+
+    // 绑定后立即释放的端口上没有服务在监听
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let server = format!("127.0.0.1:{}", port);
+    let unreachable_run = run(
+        "check-id-unreachable",
+        &["check-id", CUSTOM_ID, "--id-server", &server, "--timeout", "1"],
+        "",
+    );
+    assert!(!unreachable_run.output.status.success());
+    assert!(unreachable_run
+        .stdout()
+        .contains(&format!("Cannot reach the ID server {}", server)));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn register_requires_key_pair() {
+    let dir = scratch_dir("register");
+    let config = dir.join("RustDesk.toml");
+    fs::write(&config, format!("enc_id = '{}'\n", ENC_ID)).unwrap();
+
+    let output = run(
+        "register-no-key-pair",
+        &[
+            "register",
+            "--uuid",
+            UUID,
+            "--config",
+            config.to_str().unwrap(),
+            "--id-server",
+            "127.0.0.1",
+        ],
+        "",
+    );
+    assert!(!output.output.status.success());
+    assert!(output.stdout().contains("has no key pair"));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn auto_id_derives_id_from_mac() {
+    let output = run("auto-id", &["auto-id", "00:11:22:33:44:55", "00-00-00-00-01-00"], "");
+    assert!(output.output.status.success());
+    let stdout = output.stdout();
+    assert!(stdout.contains("00:11:22:33:44:55 → 36914261"));
+    assert!(stdout.contains("00-00-00-00-01-00 → 256"));
+    assert!(stdout.contains("256 is shorter than 6 digits"));
+
+    let invalid = run("auto-id-invalid", &["auto-id", "not-a-mac"], "");
+    assert!(!invalid.output.status.success());
+    assert!(invalid.stdout().contains("Invalid MAC address not-a-mac"));
+}
+
+#[test]
+fn uuid_reports_effective_key() {
+    let output = run(
+        "uuid-key",
+        &[
+            "uuid",
+            UUID,
+            "12345678-1234-1234-1234-12345678ffff",
+            "12345678123412341234123456789012",
+        ],
+        "",
+    );
+    assert!(output.output.status.success());
+    let stdout = output.stdout();
+    assert!(stdout.contains("(32 of 36 bytes used)"));
+    assert!(stdout.contains("(32 of 32 bytes used)"));
+    assert!(stdout.contains("The last 4 bytes are cut off"));
+    assert_eq!(stdout.matches("give the same key").count(), 1);
+
+    // 转换为带连字符的写法后与直接使用该写法的结果相同
+    let converted = run(
+        "uuid-form",
+        &[
+            "encrypt",
+            CUSTOM_ID,
+            "--uuid",
+            "12345678123412341234123456789012",
+            "--uuid-form",
+            "hyphenated",
+            "--no-clipboard",
+        ],
+        "",
+    );
+    assert!(converted.output.status.success());
+    assert!(converted.stdout().contains(ENC_ID));
+
+    let invalid = run("uuid-key-invalid", &["uuid", "not-a-uuid"], "");
+    assert!(!invalid.output.status.success());
+}
+
+#[test]
+fn tui_checks_uuid_form_before_starting() {
+    let run = run(
+        "tui-uuid-form",
+        &["tui", "--uuid", "not-a-uuid", "--uuid-form", "raw"],
+        "",
+    );
+    assert!(!run.output.status.success());
+    assert!(run.stdout().contains("Invalid UUID format"));
+}
+
+/// 在后台运行的 `serve` 进程，测试结束时终止
+struct ApiServer {
+    child: Child,
+    address: String,
+    /// 保持标准输出打开，避免服务在启动后继续输出时因管道关闭而退出
+    _stdout: BufReader<ChildStdout>,
+}
+
+impl ApiServer {
+    /// 在系统分配的本机端口上启动服务，从第一行输出中读取监听地址
+    fn start(token: &str, args: &[&str]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_custom-rustdesk"))
+            .args(["serve", "--bind", "127.0.0.1:0"])
+            .args(args)
+            .env("CUSTOM_RUSTDESK_API_TOKEN", token)
+            .env("LANG", "C")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to spawn custom-rustdesk");
+
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        let address = line
+            .split("http://")
+            .nth(1)
+            .expect("listening address")
+            .trim()
+            .to_string();
+        ApiServer {
+            child,
+            address,
+            _stdout: stdout,
+        }
+    }
+
+    /// 发送一个 HTTP 请求，返回状态码和解析后的 JSON
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        token: Option<&str>,
+        body: &str,
+    ) -> (u16, serde_json::Value) {
+        let mut stream = TcpStream::connect(&self.address).unwrap();
+        let authorization = token
+            .map(|token| format!("Authorization: Bearer {}\r\n", token))
+            .unwrap_or_default();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\n{}Content-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            self.address,
+            authorization,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").expect("HTTP response");
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).expect("valid JSON"))
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn serve_answers_over_loopback() {
+    let server = ApiServer::start("test-token", &[]);
+    assert!(server.address.starts_with("127.0.0.1:"));
+
+    let (status, health) = server.request("GET", "/health", None, "");
+    assert_eq!((status, health["status"].as_str()), (200, Some("ok")));
+
+    let encrypt = format!(r#"{{"id":"{}","uuid":"{}"}}"#, CUSTOM_ID, UUID);
+    let (status, error) = server.request("POST", "/encrypt", None, &encrypt);
+    assert_eq!(
+        (status, error["error"]["kind"].as_str()),
+        (401, Some("unauthorized"))
+    );
+    let (status, error) = server.request("POST", "/encrypt", Some("wrong"), &encrypt);
+    assert_eq!(status, 401);
+    assert_eq!(error["error"]["message"], "Missing or invalid API token");
+
+    let (status, encrypted) = server.request("POST", "/encrypt", Some("test-token"), &encrypt);
+    assert_eq!((status, encrypted["enc_id"].as_str()), (200, Some(ENC_ID)));
+
+    let decrypt = format!(r#"{{"enc_id":"{}","uuid":"{}"}}"#, ENC_ID, UUID);
+    let (status, decrypted) = server.request("POST", "/decrypt", Some("test-token"), &decrypt);
+    assert_eq!((status, decrypted["id"].as_str()), (200, Some(CUSTOM_ID)));
+
+    let (status, error) = server.request(
+        "POST",
+        "/decrypt",
+        Some("test-token"),
+        &format!(r#"{{"enc_id":"{}","uuid":"not-a-uuid"}}"#, ENC_ID),
+    );
+    assert_eq!(
+        (status, error["error"]["kind"].as_str()),
+        (400, Some("validation"))
+    );
+    assert!(error["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid UUID format"));
+
+    let (status, generated) = server.request(
+        "POST",
+        "/generate",
+        Some("test-token"),
+        &format!(r#"{{"uuid":"{}","count":2}}"#, UUID),
+    );
+    assert_eq!(status, 200);
+    assert_eq!(generated["ids"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn serve_answers_health_while_check_id_waits() {
+    // 接受连接但从不回复的 ID 服务器
+    let silent = TcpListener::bind("127.0.0.1:0").unwrap();
+    let id_server = silent.local_addr().unwrap().to_string();
+    let server = ApiServer::start("test-token", &["--id-server", &id_server]);
+
+    let (status, error) = thread::scope(|scope| {
+        let check = scope.spawn(|| {
+            server.request(
+                "POST",
+                "/check-id",
+                Some("test-token"),
+                &format!(r#"{{"id":"{}"}}"#, CUSTOM_ID),
+            )
+        });
+        thread::sleep(Duration::from_millis(500));
+
+        let started = Instant::now();
+        let (status, health) = server.request("GET", "/health", None, "");
+        assert_eq!((status, health["status"].as_str()), (200, Some("ok")));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        check.join().unwrap()
+    });
+    assert_eq!(
+        (status, error["error"]["kind"].as_str()),
+        (502, Some("network"))
+    );
+}
+
+#[test]
+fn batch_encrypts_and_reports_failures() {
+    let dir = scratch_dir("batch");
+    let input = dir.join("ids.txt");
+    let output = dir.join("out.csv");
+    fs::write(
+        &input,
+        format!("# comment\n{}\nsecond,{}\n\nbad\tid\n", CUSTOM_ID, UUID),
+    )
+    .unwrap();
+
+    let args = [
+        "batch",
+        input.to_str().unwrap(),
+        "--uuid",
+        UUID,
+        "--output",
+        output.to_str().unwrap(),
+    ];
+    let batch = run("batch-run", &args, "");
+
+    assert!(!batch.output.status.success());
+    assert!(batch.stderr().contains("Line 5"));
+    let csv = fs::read_to_string(&output).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "custom_id,uuid,enc_id");
+    assert!(lines[1].starts_with(&format!("{},", CUSTOM_ID)));
+    assert!(lines[1].ends_with(ENC_ID));
+    assert!(lines[2].starts_with("second,1234****"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&output).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // 不覆盖已有的结果文件
+    let again = run("batch-exists", &args, "");
+    assert!(!again.output.status.success());
+    assert!(again.stdout().contains("already exists"));
+    assert_eq!(fs::read_to_string(&output).unwrap(), csv);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn batch_seal_and_unseal() {
+    let dir = scratch_dir("batch-seal");
+    let input = dir.join("ids.txt");
+    let sealed = dir.join("out.sealed");
+    let unsealed = dir.join("out.csv");
+    fs::write(&input, format!("{}\n", CUSTOM_ID)).unwrap();
+    let passphrase = [("CUSTOM_RUSTDESK_PASSPHRASE", "correct horse")];
+
+    let from_stdin = run(
+        "batch-seal-stdin",
+        &["batch", "-", "--uuid", UUID, "--seal"],
+        &format!("{}\n", CUSTOM_ID),
+    );
+    assert!(!from_stdin.output.status.success());
+    assert!(from_stdin.stdout().contains("--passphrase"));
+
+    let batch = run_with_env(
+        "batch-seal-run",
+        &[
+            "batch",
+            input.to_str().unwrap(),
+            "--uuid",
+            UUID,
+            "--seal",
+            "--output",
+            sealed.to_str().unwrap(),
+        ],
+        "",
+        &passphrase,
+    );
+    assert!(batch.output.status.success());
+    let content = fs::read_to_string(&sealed).unwrap();
+    assert!(!content.contains(CUSTOM_ID));
+    assert!(!content.contains(ENC_ID));
+
+    let wrong = run_with_env(
+        "unseal-wrong",
+        &["unseal", sealed.to_str().unwrap()],
+        "",
+        &[("CUSTOM_RUSTDESK_PASSPHRASE", "wrong")],
+    );
+    assert!(!wrong.output.status.success());
+    assert!(wrong.stdout().contains("Wrong passphrase"));
+
+    let unseal = run_with_env(
+        "unseal-stdout",
+        &["unseal", sealed.to_str().unwrap()],
+        "",
+        &passphrase,
+    );
+    assert!(unseal.output.status.success());
+    assert!(unseal.stdout().starts_with("custom_id,uuid,enc_id\n"));
+    assert!(unseal.stdout().contains(ENC_ID));
+
+    let args = [
+        "unseal",
+        sealed.to_str().unwrap(),
+        "--output",
+        unsealed.to_str().unwrap(),
+    ];
+    let unseal = run_with_env("unseal-file", &args, "", &passphrase);
+    assert!(unseal.output.status.success());
+    assert!(fs::read_to_string(&unsealed).unwrap().contains(ENC_ID));
+    let again = run_with_env("unseal-exists", &args, "", &passphrase);
+    assert!(!again.output.status.success());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn no_clipboard_skips_copy() {
+    let run = run(
+        "no-clipboard",
+        &["encrypt", CUSTOM_ID, "--uuid", UUID, "--no-clipboard"],
+        "",
+    );
+
+    assert!(run.output.status.success());
+    assert!(!run.stdout().contains("clipboard"));
+    assert_eq!(run.clipboard, None);
+}
+
+#[test]
+fn clipboard_file_mode_writes_file() {
+    let dir = scratch_dir("clipboard-file");
+    let target = dir.join("copied.txt");
+    let run = run(
+        "clipboard-file-run",
+        &[
+            "encrypt",
+            CUSTOM_ID,
+            "--uuid",
+            UUID,
+            "--clipboard",
+            "file",
+            "--clipboard-file",
+            target.to_str().unwrap(),
+        ],
+        "",
+    );
+
+    assert!(run.output.status.success());
+    assert_eq!(read_optional(&target).as_deref(), Some(ENC_ID));
+    assert_eq!(run.clipboard, None);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn osc52_sequence_stays_out_of_stdout() {
+    let run = run(
+        "clipboard-osc52",
+        &["encrypt", CUSTOM_ID, "--uuid", UUID, "--clipboard", "osc52"],
+        "",
+    );
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains(ENC_ID));
+    assert!(!run.stdout().contains("\x1b]52;"));
+}
+
+#[test]
+fn clipboard_is_cleared_after_timeout() {
+    let run = run(
+        "clipboard-clear",
+        &[
+            "encrypt",
+            CUSTOM_ID,
+            "--uuid",
+            UUID,
+            "--clipboard-clear-after",
+            "1",
+        ],
+        "",
+    );
+
+    assert!(run.output.status.success());
+    let stdout = run.stdout();
+    assert!(stdout.contains("Copied to clipboard"));
+    assert!(stdout.contains("Clipboard cleared"));
+    assert_eq!(run.clipboard, None);
+}
+
+#[test]
+fn chinese_output_with_lang_flag() {
+    let run = run(
+        "lang-zh",
+        &["encrypt", CUSTOM_ID, "--uuid", UUID, "--lang", "zh"],
+        "",
+    );
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains("使用说明"));
+}
+
+#[test]
+fn language_is_detected_from_environment() {
+    let run = run_with_env(
+        "lang-env",
+        &["encrypt", CUSTOM_ID, "--uuid", UUID],
+        "",
+        &[("LANG", "zh_CN.UTF-8")],
+    );
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains("使用说明"));
+}
+
+#[test]
+fn lang_flag_overrides_environment() {
+    let run = run_with_env(
+        "lang-override",
+        &["encrypt", CUSTOM_ID, "--uuid", UUID, "--lang", "en"],
+        "",
+        &[("LC_ALL", "zh_CN.UTF-8")],
+    );
+
+    assert!(run.output.status.success());
+    assert!(!run.stdout().contains("使用说明"));
+}
+
+#[test]
+fn unsupported_lang_warns_and_uses_english() {
+    let run = run(
+        "lang-unsupported",
+        &["encrypt", CUSTOM_ID, "--uuid", UUID, "--lang", "xx"],
+        "",
+    );
+
+    assert!(run.output.status.success());
+    assert!(run.stderr().contains("Unsupported language 'xx'"));
+    assert!(run.stdout().contains(ENC_ID));
+}
+
+#[test]
+fn unsupported_environment_locale_warns_once() {
+    let run = run_with_env(
+        "lang-env-unsupported",
+        &["encrypt", CUSTOM_ID, "--uuid", UUID],
+        "",
+        &[("LANG", "ko_KR.UTF-8")],
+    );
+
+    assert!(run.output.status.success());
+    assert_eq!(
+        run.stderr()
+            .matches("Unsupported language 'ko_KR.UTF-8'")
+            .count(),
+        1
+    );
+    assert!(run.stdout().contains(ENC_ID));
+}
+
+#[test]
+fn lang_list_shows_available_locales() {
+    let run = run("lang-list", &["--lang", "list"], "");
+
+    assert!(run.output.status.success());
+    let stdout = run.stdout();
+    assert!(stdout.contains("* en"));
+    assert!(stdout.contains("zh"));
+    assert!(stdout.contains("简体中文"));
+}
+
+#[test]
+fn help_flag_prints_help() {
+    let run = run("help", &["--help"], "");
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains("--clipboard-clear-after <SECS>"));
+    assert!(run.stdout().contains("Config File Location"));
+}
+
+#[test]
+fn subcommand_help_is_localized() {
+    let run = run("help-zh", &["apply", "--help", "--lang", "zh"], "");
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains("--dry-run"));
+    assert!(run.stdout().contains("只显示修改内容，不写入文件"));
+}
+
+#[test]
+fn completions_are_generated() {
+    for shell in ["bash", "zsh", "fish", "powershell"] {
+        let run = run("completions", &["completions", shell], "");
+
+        assert!(run.output.status.success());
+        assert!(run.stdout().contains("custom-rustdesk"));
+        assert!(run.stdout().contains("clipboard-clear-after"));
+    }
+}
+
+#[test]
+fn man_page_is_generated() {
+    let run = run("man", &["man"], "");
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().starts_with(".ie"));
+    assert!(run.stdout().contains(".TH custom-rustdesk 1"));
+}
+
+#[test]
+fn confirmed_machine_uuid_is_used() {
+    let Some(machine_uuid) = machine_uuid() else {
+        return;
+    };
+    let run = run("confirm-yes", &["encrypt", CUSTOM_ID], "y\n");
+
+    assert!(run.output.status.success());
+    let stdout = run.stdout();
+    assert!(!stdout.contains(&machine_uuid));
+    assert!(stdout.contains(&machine_uuid[..4]));
+    assert!(stdout.contains("--show-uuid"));
+    assert!(stdout.contains("is encrypted to"));
+    assert!(run.clipboard.is_some());
+}
+
+#[test]
+fn show_uuid_reveals_machine_uuid() {
+    let Some(machine_uuid) = machine_uuid() else {
+        return;
+    };
+    let run = run("show-uuid", &["encrypt", CUSTOM_ID, "--show-uuid"], "y\n");
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains(&machine_uuid));
+}
+
+#[test]
+fn rejected_machine_uuid_falls_back_to_manual_input() {
+    if machine_uuid().is_none() {
+        return;
+    }
+    let run = run("confirm-no", &["encrypt", CUSTOM_ID], &format!("n\n{}\n", UUID));
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains("Please enter UUID manually"));
+    assert_eq!(run.clipboard.as_deref(), Some(ENC_ID));
+}
+
+#[test]
+fn empty_manual_uuid_cancels() {
+    if machine_uuid().is_none() {
+        return;
+    }
+    let run = run("confirm-cancel", &["encrypt", CUSTOM_ID], "n\n\n");
+
+    assert!(run.stdout().contains("Operation cancelled by user"));
+    assert_eq!(run.clipboard, None);
+}
+
+#[test]
+fn interactive_encrypt() {
+    if machine_uuid().is_none() {
+        return;
+    }
+    let stdin = format!("1\n{}\nn\n{}\n", CUSTOM_ID, UUID);
+    let run = run("interactive-encrypt", &[], &stdin);
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains("Generate Custom ID (Encrypt Mode)"));
+    assert_eq!(run.clipboard.as_deref(), Some(ENC_ID));
+}
+
+#[test]
+fn interactive_decrypt() {
+    if machine_uuid().is_none() {
+        return;
+    }
+    let stdin = format!("2\n{}\nn\n{}\n", ENC_ID, UUID);
+    let run = run("interactive-decrypt", &[], &stdin);
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains("Validate Encrypted ID (Decrypt Mode)"));
+    assert_eq!(run.clipboard.as_deref(), Some(CUSTOM_ID));
+}
+
+#[test]
+fn interactive_invalid_choice_then_exit() {
+    let run = run("interactive-exit", &[], "9\n0\n");
+
+    assert!(run.output.status.success());
+    let stdout = run.stdout();
+    assert!(stdout.contains("Invalid choice"));
+    assert!(stdout.contains("goodbye"));
+    assert_eq!(run.clipboard, None);
+}
+
+#[test]
+fn interactive_exits_at_end_of_input() {
+    let run = run("interactive-eof", &[], "");
+
+    assert!(run.output.status.success());
+    assert!(run.stdout().contains("goodbye"));
+}
+
+#[test]
+fn interactive_session_reuses_confirmed_uuid() {
+    if machine_uuid().is_none() {
+        return;
+    }
+    let stdin = format!("1\n{}\nn\n{}\n2\n{}\n0\n", CUSTOM_ID, UUID, ENC_ID);
+    let run = run("interactive-session", &[], &stdin);
+
+    assert!(run.output.status.success());
+    let stdout = run.stdout();
+    assert_eq!(stdout.matches("Use this UUID?").count(), 1);
+    assert!(stdout.contains("confirmed earlier in this session"));
+    assert!(stdout.contains(&format!("is encrypted to \"{}\"", ENC_ID)));
+    assert_eq!(run.clipboard.as_deref(), Some(CUSTOM_ID));
+}
+
+#[test]
+fn interactive_apply_and_inspect_config() {
+    if machine_uuid().is_none() {
+        return;
+    }
+    let dir = scratch_dir("interactive-config");
+    let config = dir.join("RustDesk.toml");
+    fs::write(&config, "enc_id = '00old'\n").unwrap();
+    let path = config.to_str().unwrap();
+
+    let stdin = format!("3\n{}\n\n{}\nn\n{}\n4\n{}\n0\n", CUSTOM_ID, path, UUID, path);
+    let run = run("interactive-config-run", &[], &stdin);
+
+    assert!(run.output.status.success());
+    let stdout = run.stdout();
+    assert!(stdout.contains(&format!("+ enc_id = \"{}\"", ENC_ID)));
+    assert!(stdout.contains(&format!("ID: {}", CUSTOM_ID)));
+    assert!(fs::read_to_string(&config).unwrap().contains(ENC_ID));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn end_of_input_at_uuid_confirmation_cancels() {
+    if machine_uuid().is_none() {
+        return;
+    }
+    let run = run("confirm-eof", &["encrypt", CUSTOM_ID], "");
+
+    assert!(!run.output.status.success());
+    assert!(run.stdout().contains("Operation cancelled by user"));
+    assert_eq!(run.clipboard, None);
+}
+
+#[test]
+fn end_of_input_at_password_prompt_cancels() {
+    let run = run("password-eof", &["password", "encrypt", "--uuid", UUID], "");
+
+    assert!(!run.output.status.success());
+    assert!(run.stdout().contains("Operation cancelled by user"));
+    assert_eq!(run.clipboard, None);
+}
+
+#[test]
+fn prompted_password_keeps_surrounding_whitespace() {
+    let prompted = run(
+        "password-prompt-whitespace",
+        &["password", "encrypt", "--uuid", UUID],
+        " S3cret pass \r\n",
+    );
+    assert!(prompted.output.status.success());
+    let given = run(
+        "password-arg-whitespace",
+        &["password", "encrypt", " S3cret pass ", "--uuid", UUID],
+        "",
+    );
+    assert!(given.output.status.success());
+    assert!(prompted.clipboard.is_some());
+    assert_eq!(prompted.clipboard, given.clipboard);
+}
+
+#[test]
+fn interactive_cancelled_prompt_returns_to_menu() {
+    let run = run("interactive-cancel", &[], "1\n");
+
+    assert!(run.output.status.success());
+    let stdout = run.stdout();
+    assert!(stdout.contains("Operation cancelled by user"));
+    assert!(!stdout.contains("Custom ID cannot be empty"));
+    assert!(stdout.contains("goodbye"));
+}
@@ -0,0 +1,47 @@
+# RustDesk ID deployment script generated by custom-rustdesk
+# Custom ID: TestComputer
+# Run this script as Administrator on the target machine.
+$ErrorActionPreference = 'Stop'
+
+$CustomId = 'TestComputer'
+$EncIdLine = "enc_id = '00Nhyr/aazYBq3jkSuNwA6trMEV2xPMoziEAJWog=='"
+$ConfigFiles = @(
+    (Join-Path $env:APPDATA 'RustDesk\config\RustDesk.toml'),
+    'C:\Windows\ServiceProfiles\LocalService\AppData\Roaming\RustDesk\config\RustDesk.toml'
+)
+
+# RustDesk writes its configuration back when it exits, so stop it before patching
+$Service = Get-Service -Name 'RustDesk' -ErrorAction SilentlyContinue
+if ($Service -and $Service.Status -ne 'Stopped') {
+    Stop-Service -Name 'RustDesk' -Force
+}
+Get-Process -Name 'rustdesk' -ErrorAction SilentlyContinue | Stop-Process -Force
+
+foreach ($File in $ConfigFiles) {
+    if (-not (Test-Path -LiteralPath $File)) {
+        Write-Host "Skipped $File (not found)"
+        continue
+    }
+    # Never overwrite an earlier backup, it may hold the only copy of the original ID
+    $Stamp = [DateTimeOffset]::UtcNow.ToUnixTimeSeconds()
+    $Backup = "$File.bak.$Stamp"
+    $Counter = 0
+    while (Test-Path -LiteralPath $Backup) {
+        $Counter++
+        $Backup = "$File.bak.$Stamp.$Counter"
+    }
+    Copy-Item -LiteralPath $File -Destination $Backup
+    $Lines = @(Get-Content -LiteralPath $File)
+    if ($Lines -match '^enc_id\s*=') {
+        $Lines = $Lines | ForEach-Object { if ($_ -match '^enc_id\s*=') { $EncIdLine } else { $_ } }
+    } else {
+        $Lines = @($EncIdLine) + $Lines
+    }
+    [System.IO.File]::WriteAllLines($File, [string[]]$Lines)
+    Write-Host "Updated $File (backup: $Backup)"
+}
+
+if ($Service) {
+    Start-Service -Name 'RustDesk'
+}
+Write-Host "RustDesk ID set to $CustomId"
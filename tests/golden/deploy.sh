@@ -0,0 +1,76 @@
+#!/bin/sh
+# RustDesk ID deployment script generated by custom-rustdesk
+# Custom ID: TestComputer
+# Run this script as root on the target machine.
+set -eu
+
+CUSTOM_ID='TestComputer'
+ENC_ID_LINE="enc_id = '00Nhyr/aazYBq3jkSuNwA6trMEV2xPMoziEAJWog=='"
+
+if [ "$(uname -s)" = Darwin ]; then
+    CONFIG_DIRS="$HOME/Library/Preferences/com.carriez.RustDesk /var/root/Library/Preferences/com.carriez.RustDesk"
+    LAUNCH_DAEMON=/Library/LaunchDaemons/com.carriez.RustDesk_service.plist
+else
+    CONFIG_DIRS="$HOME/.config/rustdesk /root/.config/rustdesk"
+fi
+
+# RustDesk writes its configuration back when it exits, so stop it before patching
+stop_service() {
+    if [ -n "${LAUNCH_DAEMON:-}" ]; then
+        launchctl unload "$LAUNCH_DAEMON" 2>/dev/null || true
+    elif command -v systemctl >/dev/null 2>&1; then
+        systemctl stop rustdesk 2>/dev/null || true
+    fi
+}
+
+start_service() {
+    if [ -n "${LAUNCH_DAEMON:-}" ]; then
+        launchctl load -w "$LAUNCH_DAEMON" 2>/dev/null || true
+    elif command -v systemctl >/dev/null 2>&1; then
+        systemctl start rustdesk 2>/dev/null || true
+    fi
+}
+
+patch_config() {
+    file="$1/RustDesk.toml"
+    if [ ! -f "$file" ]; then
+        echo "Skipped $file (not found)"
+        return 0
+    fi
+    # Never overwrite an earlier backup, it may hold the only copy of the original ID.
+    # With noclobber the redirection fails if the name is taken, which reserves it atomically.
+    stamp=$(date +%s)
+    backup="$file.bak.$stamp"
+    counter=0
+    while ! (set -C; : > "$backup") 2>/dev/null; do
+        if [ ! -e "$backup" ]; then
+            echo "Cannot create backup $backup" >&2
+            exit 1
+        fi
+        counter=$((counter + 1))
+        backup="$file.bak.$stamp.$counter"
+    done
+    cp -p "$file" "$backup"
+    if grep -q '^enc_id[[:space:]]*=' "$file"; then
+        sed "s|^enc_id[[:space:]]*=.*|$ENC_ID_LINE|" "$backup" > "$file"
+    else
+        { echo "$ENC_ID_LINE"; cat "$backup"; } > "$file"
+    fi
+    echo "Updated $file (backup: $backup)"
+}
+
+# Run as root, $HOME is one of the fixed directories; patch each file only once
+UNIQUE_DIRS=
+for dir in $CONFIG_DIRS; do
+    case " $UNIQUE_DIRS " in
+        *" $dir "*) ;;
+        *) UNIQUE_DIRS="$UNIQUE_DIRS $dir" ;;
+    esac
+done
+
+stop_service
+for dir in $UNIQUE_DIRS; do
+    patch_config "$dir"
+done
+start_service
+echo "RustDesk ID set to $CUSTOM_ID"
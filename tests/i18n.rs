@@ -0,0 +1,186 @@
+//! 翻译完整性测试
+//!
+//! 检查源代码中通过 `t!` 使用的每个键（包括运行时选择的键）都存在于所有语言文件中，
+//! 并且各语言文件的键和 `%{}` 占位符与英文文件一致。
+
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 作为基准的语言
+const BASE_LOCALE: &str = "en";
+
+/// 先存入变量再传给 `t!` 的键，无法从源代码中直接找到，修改相应代码时需同步更新
+///
+/// 帮助文本中的 `cli_*` 键缺失时会使用定义中的英文文本，不在此列。
+const RUNTIME_KEYS: &[&str] = &[
+    // commands.rs: rekey_config
+    "rekey_field_rekeyed",
+    "rekey_field_already_rekeyed",
+    // commands.rs: run_check_id
+    "check_id_available",
+    "check_id_offline",
+    "check_id_online",
+    // interactive.rs: show_menu_options
+    "generate_id",
+    "generate_id_desc",
+    "validate_id",
+    "validate_id_desc",
+    "apply_config",
+    "apply_config_desc",
+    "inspect_config",
+    "inspect_config_desc",
+    "encrypt_password",
+    "encrypt_password_desc",
+    "decrypt_password",
+    "decrypt_password_desc",
+    "help",
+    "help_desc",
+    "change_uuid",
+    "change_uuid_desc",
+    // help.rs: long_help_footer
+    "program_function_title",
+    "program_function_desc1",
+    "program_function_desc2",
+    "cli_examples_title",
+    "cli_example_encrypt",
+    "cli_example_decrypt",
+    "cli_example_apply",
+    "cli_example_completions",
+    "config_file_location_title",
+    "config_file_location_macos",
+    "config_file_location_windows",
+    "config_file_location_windows_service",
+    "notes_title",
+    "note_1",
+    "note_2",
+    "note_3",
+    "get_help_title",
+    "get_help_github",
+    "get_help_email",
+];
+
+fn root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// 读取所有语言文件，返回 语言代码 -> (键 -> 文本)
+fn load_locales() -> BTreeMap<String, BTreeMap<String, String>> {
+    fs::read_dir(root().join("i18n"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "yml"))
+        .map(|path| {
+            let locale = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let content = fs::read_to_string(&path).unwrap();
+            let entries = serde_yaml::from_str(&content)
+                .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+            (locale, entries)
+        })
+        .collect()
+}
+
+/// 递归列出目录下的 Rust 源文件
+fn source_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(source_files(&path));
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// 源代码中以字符串字面量形式传给 `t!` 的键，以及运行时选择的键
+fn used_keys() -> BTreeSet<String> {
+    let pattern = Regex::new(r#"t!\(\s*"([A-Za-z0-9_.]+)""#).unwrap();
+    source_files(&root().join("src"))
+        .iter()
+        .flat_map(|path| {
+            let content = fs::read_to_string(path).unwrap();
+            pattern
+                .captures_iter(&content)
+                .map(|caps| caps[1].to_string())
+                .collect::<Vec<_>>()
+        })
+        .chain(RUNTIME_KEYS.iter().map(|key| key.to_string()))
+        .collect()
+}
+
+/// 文本中的占位符名称
+fn placeholders(pattern: &Regex, text: &str) -> BTreeSet<String> {
+    pattern
+        .captures_iter(text)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+#[test]
+fn every_used_key_is_translated() {
+    let locales = load_locales();
+    let mut missing = Vec::new();
+
+    for key in used_keys() {
+        for (locale, entries) in &locales {
+            if !entries.contains_key(&key) {
+                missing.push(format!("{}: {}", locale, key));
+            }
+        }
+    }
+
+    assert!(missing.is_empty(), "missing translations:\n{}", missing.join("\n"));
+}
+
+#[test]
+fn runtime_keys_appear_in_source() {
+    let sources: String = source_files(&root().join("src"))
+        .iter()
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect();
+    let stale: Vec<&str> = RUNTIME_KEYS
+        .iter()
+        .copied()
+        .filter(|key| !sources.contains(&format!("\"{}\"", key)))
+        .collect();
+
+    assert!(stale.is_empty(), "runtime keys not found in source: {:?}", stale);
+}
+
+#[test]
+fn locales_match_base_keys_and_placeholders() {
+    let locales = load_locales();
+    let base = &locales[BASE_LOCALE];
+    let pattern = Regex::new(r"%\{(\w+)\}").unwrap();
+    let mut problems = Vec::new();
+
+    for (locale, entries) in &locales {
+        for (key, text) in base {
+            match entries.get(key) {
+                None => problems.push(format!("{}: missing {}", locale, key)),
+                Some(translated)
+                    if placeholders(&pattern, translated) != placeholders(&pattern, text) =>
+                {
+                    problems.push(format!("{}: placeholders differ in {}", locale, key));
+                }
+                Some(_) => {}
+            }
+        }
+        for key in entries.keys().filter(|key| !base.contains_key(*key)) {
+            problems.push(format!("{}: unknown key {}", locale, key));
+        }
+    }
+
+    assert!(problems.is_empty(), "{}", problems.join("\n"));
+}
+
+#[test]
+fn expected_locales_are_shipped() {
+    let locales = load_locales();
+    for locale in ["en", "zh", "zh-TW", "ja", "de", "fr", "ru", "es"] {
+        assert!(locales.contains_key(locale), "missing locale {}", locale);
+    }
+}